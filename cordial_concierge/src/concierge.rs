@@ -0,0 +1,378 @@
+//! The `concierge` module contains the [`Concierge`] struct, a typed client for the routes of the
+//! host, so callers trade in [`Guest`] entries and [`FauxPas`] errors rather than raw requests.
+use crate::directions::Directions;
+use cordial_guest::{Audit, Guest, Inquiry, Review, Vitals};
+use cordial_improv::{Appraisal, Bulk, Candidate, Pass, Passphrase, Phrase, Vacancy};
+use polite::{FauxPas, Flaw, Polite, Problem};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use tracing::{trace, warn};
+use uuid::Uuid;
+
+/// The `Concierge` struct sends requests to the host on behalf of a caller.  Error responses are
+/// mapped back into a [`FauxPas`]: a `422` listing flaws becomes [`FauxPas::Invalid`], a `401` or
+/// `403` becomes [`FauxPas::Auth`], and any other error status becomes [`FauxPas::Status`].
+#[derive(Debug, Clone)]
+pub struct Concierge {
+    client: Client,
+    base: Url,
+    directions: Directions,
+}
+
+impl Concierge {
+    /// The header naming the caller, sent when [`Directions::actor`] is set.
+    pub const ACTOR: &'static str = "x-actor";
+
+    /// Creates a `Concierge` following `directions`.  Commits a [`FauxPas`] if the base URL cannot
+    /// be parsed or the HTTP client cannot be built.
+    pub fn new(directions: Directions) -> Polite<Self> {
+        let base = Url::parse(&directions.base)?;
+        if base.cannot_be_a_base() {
+            return Err(FauxPas::Invalid(vec![Flaw::new(
+                "base",
+                "format",
+                &format!("Cannot resolve routes below {}.", directions.base),
+            )]));
+        }
+        let client = Client::builder()
+            .timeout(directions.timeout)
+            .cookie_store(directions.cookies)
+            .build()?;
+        Ok(Self {
+            client,
+            base,
+            directions,
+        })
+    }
+
+    /// The `directions` method returns the settings of the `Concierge`.
+    pub fn directions(&self) -> &Directions {
+        &self.directions
+    }
+
+    /// The `live` method succeeds if the server process is responsive.
+    pub async fn live(&self) -> Polite<()> {
+        self.call(Method::GET, &["health", "live"], |req| req)
+            .await?;
+        Ok(())
+    }
+
+    /// The `ready` method returns the [`Vitals`] of the database behind the server.  Succeeds
+    /// whether or not the server is ready; check [`Vitals::ready`].
+    pub async fn ready(&self) -> Polite<Vitals> {
+        let url = self.locate(&["health", "ready"]);
+        let response = self.send(false, || self.client.get(url.clone())).await?;
+        match response.status() {
+            StatusCode::SERVICE_UNAVAILABLE => Ok(response.json().await?),
+            _ => Ok(Self::answer(response).await?.json().await?),
+        }
+    }
+
+    /// The `book` method returns the version of the database behind the server.
+    pub async fn book(&self) -> Polite<String> {
+        let response = self.call(Method::GET, &["book"], |req| req).await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `openapi` method returns the OpenAPI document describing the routes of the server.
+    pub async fn openapi(&self) -> Polite<serde_json::Value> {
+        let response = self.call(Method::GET, &["openapi.json"], |req| req).await?;
+        Ok(response.json().await?)
+    }
+
+    /// The `metrics` method returns the metrics of the server in the Prometheus text format.
+    pub async fn metrics(&self) -> Polite<String> {
+        let response = self.call(Method::GET, &["metrics"], |req| req).await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `guest` method looks up the [`Guest`] with `id`.
+    pub async fn guest(&self, id: Uuid) -> Polite<Guest> {
+        self.json(Method::GET, &["guests", &id.to_string()], |req| req)
+            .await
+    }
+
+    /// The `guest_named` method looks up the [`Guest`] called `name`, ignoring case and Unicode
+    /// compatibility differences.
+    pub async fn guest_named(&self, name: &str) -> Polite<Guest> {
+        self.json(Method::GET, &["guests", "by-name", name], |req| req)
+            .await
+    }
+
+    /// The `guests` method returns the [`Guest`] entries matching `inquiry`, in the order it
+    /// specifies.
+    pub async fn guests(&self, inquiry: &Inquiry) -> Polite<Vec<Guest>> {
+        self.json(Method::GET, &["guests"], |req| req.query(inquiry))
+            .await
+    }
+
+    /// The `check_in` method enters `guest` into the book, returning the entry created.
+    pub async fn check_in(&self, guest: &Guest) -> Polite<Guest> {
+        self.json(Method::POST, &["guests"], |req| req.json(guest))
+            .await
+    }
+
+    /// The `update` method updates the `name` and `hash` of `guest`, returning the entry updated.
+    pub async fn update(&self, guest: &Guest) -> Polite<Guest> {
+        self.json(Method::PUT, &["guests", &guest.id.to_string()], |req| {
+            req.json(guest)
+        })
+        .await
+    }
+
+    /// The `check_out` method marks `guest` as deleted.
+    pub async fn check_out(&self, guest: &Guest) -> Polite<()> {
+        self.call(Method::DELETE, &["guests", &guest.id.to_string()], |req| {
+            req
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// The `check_in_batch` method enters every one of `guests` as a single unit, returning the
+    /// entries created.
+    pub async fn check_in_batch(&self, guests: &[Guest]) -> Polite<Vec<Guest>> {
+        self.json(Method::POST, &["guests", "batch"], |req| req.json(guests))
+            .await
+    }
+
+    /// The `update_batch` method updates every one of `guests` as a single unit, returning the
+    /// entries updated.
+    pub async fn update_batch(&self, guests: &[Guest]) -> Polite<Vec<Guest>> {
+        self.json(Method::PUT, &["guests", "batch"], |req| req.json(guests))
+            .await
+    }
+
+    /// The `check_out_batch` method marks every one of `guests` as deleted.
+    pub async fn check_out_batch(&self, guests: &[Guest]) -> Polite<()> {
+        self.call(Method::DELETE, &["guests", "batch"], |req| req.json(guests))
+            .await?;
+        Ok(())
+    }
+
+    /// The `departed` method returns the deleted [`Guest`] entries not yet purged.
+    pub async fn departed(&self) -> Polite<Vec<Guest>> {
+        self.json(Method::GET, &["admin", "guests", "deleted"], |req| req)
+            .await
+    }
+
+    /// The `restore` method returns the deleted [`Guest`] with `id` to the book.
+    pub async fn restore(&self, id: Uuid) -> Polite<Guest> {
+        self.json(
+            Method::POST,
+            &["admin", "guests", &id.to_string(), "restore"],
+            |req| req,
+        )
+        .await
+    }
+
+    /// The `purge` method permanently removes the deleted [`Guest`] with `id`.
+    pub async fn purge(&self, id: Uuid) -> Polite<()> {
+        self.call(
+            Method::DELETE,
+            &["admin", "guests", &id.to_string()],
+            |req| req,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The `sweep` method purges every deleted [`Guest`] past the retention period, returning the
+    /// number removed.
+    pub async fn sweep(&self) -> Polite<u64> {
+        self.json(Method::POST, &["admin", "guests", "sweep"], |req| req)
+            .await
+    }
+
+    /// The `audits` method returns the [`Audit`] entries matching the filters in `review`, newest
+    /// first.
+    pub async fn audits(&self, review: &Review) -> Polite<Vec<Audit>> {
+        self.json(Method::GET, &["admin", "audits"], |req| req.query(review))
+            .await
+    }
+
+    /// The `name` method returns an improvised name.
+    pub async fn name(&self) -> Polite<String> {
+        let response = self
+            .call(Method::GET, &["improv", "name"], |req| req)
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `numbered_name` method returns an improvised name ending in digits.
+    pub async fn numbered_name(&self) -> Polite<String> {
+        let response = self
+            .call(Method::GET, &["improv", "name", "num"], |req| req)
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `vacancy` method returns the names offered for `vacancy`, a single name unless it sets
+    /// `near` or `count`.
+    pub async fn vacancy(&self, vacancy: &Vacancy) -> Polite<Vec<String>> {
+        let response = self
+            .call(Method::GET, &["improv", "name"], |req| req.query(vacancy))
+            .await?;
+        let json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .is_some_and(|kind| kind.as_bytes().starts_with(b"application/json"));
+        match json {
+            true => Ok(response.json().await?),
+            false => Ok(vec![response.text().await?]),
+        }
+    }
+
+    /// The `pass` method returns an improvised password with the default settings.
+    pub async fn pass(&self) -> Polite<String> {
+        let response = self
+            .call(Method::GET, &["improv", "pass"], |req| req)
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `pass_with` method returns an improvised password following `pass`.
+    pub async fn pass_with(&self, pass: &Pass) -> Polite<String> {
+        let response = self
+            .call(Method::POST, &["improv", "pass"], |req| req.json(pass))
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// The `names` method returns the numbered names described by `bulk`.
+    pub async fn names(&self, bulk: &Bulk) -> Polite<Vec<String>> {
+        self.json(Method::GET, &["improv", "names"], |req| req.query(bulk))
+            .await
+    }
+
+    /// The `passes` method returns the passwords following `pass`, described by `bulk`.
+    pub async fn passes(&self, bulk: &Bulk, pass: &Pass) -> Polite<Vec<String>> {
+        self.json(Method::POST, &["improv", "passes"], |req| {
+            req.query(bulk).json(pass)
+        })
+        .await
+    }
+
+    /// The `improvise` method returns the guests described by `bulk`, without entering them into
+    /// the book.
+    pub async fn improvise(&self, bulk: &Bulk) -> Polite<Vec<Guest>> {
+        self.json(Method::GET, &["improv", "guests"], |req| req.query(bulk))
+            .await
+    }
+
+    /// The `passphrase` method returns a passphrase following `passphrase`, with its entropy.
+    pub async fn passphrase(&self, passphrase: &Passphrase) -> Polite<Phrase> {
+        self.json(Method::POST, &["improv", "passphrase"], |req| {
+            req.json(passphrase)
+        })
+        .await
+    }
+
+    /// The `score` method appraises the password of `candidate` against the default policy.
+    pub async fn score(&self, candidate: &Candidate) -> Polite<Appraisal> {
+        self.json(Method::POST, &["improv", "score"], |req| {
+            req.json(candidate)
+        })
+        .await
+    }
+
+    /// Resolves the route made of `segments` below the base URL, escaping each segment.
+    fn locate(&self, segments: &[&str]) -> Url {
+        let mut url = self.base.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        url
+    }
+
+    /// Sends the request to the route made of `segments`, letting `prepare` add the query and
+    /// body, and maps an error response into a [`FauxPas`].
+    async fn call<F>(&self, method: Method, segments: &[&str], prepare: F) -> Polite<Response>
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let url = self.locate(segments);
+        let safe = method.is_idempotent();
+        let response = self
+            .send(safe, || {
+                prepare(self.client.request(method.clone(), url.clone()))
+            })
+            .await?;
+        Self::answer(response).await
+    }
+
+    /// Sends the request as in [`Concierge::call`], then reads the body as JSON.
+    async fn json<T, F>(&self, method: Method, segments: &[&str], prepare: F) -> Polite<T>
+    where
+        T: DeserializeOwned,
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        Ok(self.call(method, segments, prepare).await?.json().await?)
+    }
+
+    /// Sends the request built by `request`, retrying as the [`Directions`] allow.  Only requests
+    /// that are `safe` to repeat are retried after a timeout or a `502`, `503` or `504` status.
+    async fn send<F>(&self, safe: bool, request: F) -> Polite<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let mut builder = request();
+            if let Some(token) = &self.directions.bearer {
+                builder = builder.bearer_auth(token);
+            }
+            if let Some(actor) = &self.directions.actor {
+                builder = builder.header(Self::ACTOR, actor);
+            }
+            let result = builder.send().await;
+            let retry = match &result {
+                Ok(response) => {
+                    safe && matches!(
+                        response.status(),
+                        StatusCode::BAD_GATEWAY
+                            | StatusCode::SERVICE_UNAVAILABLE
+                            | StatusCode::GATEWAY_TIMEOUT
+                    )
+                }
+                Err(e) => e.is_connect() || (safe && e.is_timeout()),
+            };
+            if !retry || attempt >= self.directions.retries {
+                return Ok(result?);
+            }
+            let wait = self.directions.backoff * 2u32.saturating_pow(attempt);
+            match &result {
+                Ok(response) => warn!("Answered {}, retrying in {:?}.", response.status(), wait),
+                Err(e) => warn!("Request failed, retrying in {:?}: {}", wait, e),
+            }
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Passes a successful `response` through, and maps an error response into a [`FauxPas`].
+    async fn answer(response: Response) -> Polite<Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let message = response.text().await?;
+        trace!("Answered {}: {}", status, message);
+        let problem = serde_json::from_str::<Problem>(&message).ok();
+        Err(match (status, problem) {
+            (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, _) => FauxPas::Auth,
+            (StatusCode::UNPROCESSABLE_ENTITY, Some(problem)) if !problem.flaws.is_empty() => {
+                FauxPas::Invalid(problem.flaws)
+            }
+            (_, Some(problem)) => FauxPas::Status {
+                status: status.as_u16(),
+                message: problem.detail,
+            },
+            (_, None) => FauxPas::Status {
+                status: status.as_u16(),
+                message,
+            },
+        })
+    }
+}
@@ -0,0 +1,66 @@
+//! The `directions` module contains the [`Directions`] struct, holding the settings a
+//! [`crate::Concierge`] uses to reach the server.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The `Directions` struct holds where the server lives, how long to wait for it, how often to
+/// retry, and how to authenticate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Directions {
+    /// The `base` field holds the base URL of the server, such as `http://127.0.0.1:8000`.
+    /// Routes are resolved below its path.
+    pub base: String,
+    /// The `timeout` field holds the time allowed for each attempt at a request, from connecting
+    /// until the body is read.
+    pub timeout: Duration,
+    /// The `retries` field holds the number of times a failed request is repeated.  Requests
+    /// that could not connect are always retried, while requests answered with a `502`, `503` or
+    /// `504` status or timing out are retried only if repeating them is safe.
+    pub retries: u32,
+    /// The `backoff` field holds the wait before the first retry, doubling with each retry after.
+    pub backoff: Duration,
+    /// The `bearer` field holds a token sent in the `Authorization` header of every request, if
+    /// set.
+    pub bearer: Option<String>,
+    /// The `actor` field names the caller in the `X-Actor` header of every request, if set.  The
+    /// host attributes the changes a request makes to this name in its audit trail.
+    pub actor: Option<String>,
+    /// The `cookies` field keeps the cookies set by the server and returns them with later
+    /// requests.
+    pub cookies: bool,
+}
+
+impl Directions {
+    /// The default base URL, the local server.
+    pub const BASE: &'static str = "http://127.0.0.1:8000";
+    /// The default timeout of ten seconds.
+    pub const TIMEOUT: Duration = Duration::from_secs(10);
+    /// The default number of retries.
+    pub const RETRIES: u32 = 2;
+    /// The default wait before the first retry.
+    pub const BACKOFF: Duration = Duration::from_millis(100);
+
+    /// Creates `Directions` to the server at `base`, with the default settings.  Modify the fields
+    /// directly after construction to customize.
+    pub fn new(base: &str) -> Self {
+        Self {
+            base: base.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Directions {
+    fn default() -> Self {
+        Self {
+            base: Self::BASE.to_string(),
+            timeout: Self::TIMEOUT,
+            retries: Self::RETRIES,
+            backoff: Self::BACKOFF,
+            bearer: None,
+            actor: None,
+            cookies: true,
+        }
+    }
+}
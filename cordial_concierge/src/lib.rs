@@ -0,0 +1,5 @@
+pub mod concierge;
+pub mod directions;
+
+pub use crate::concierge::Concierge;
+pub use crate::directions::Directions;
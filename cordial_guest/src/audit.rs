@@ -0,0 +1,182 @@
+//! The `audit` module contains the [`Audit`] struct, an entry in the append-only trail of changes
+//! made to [`Guest`] entries, along with the [`Review`] filtering the trail.  The trail is
+//! recorded by the `cordial_recall` crate; the types live here so that clients can read it without
+//! building the database stack.
+use chrono::{DateTime, Utc};
+use polite::{FauxPas, Flaw, Polite};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[cfg(feature = "serial")]
+use crate::Guest;
+#[cfg(feature = "serial")]
+use serde_json::{json, Map};
+
+/// The `Action` enum names the kinds of change recorded in an [`Audit`].
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serial", serde(rename_all = "snake_case"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "sql", derive(sqlx::Type))]
+#[cfg_attr(feature = "sql", sqlx(type_name = "text", rename_all = "snake_case"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// The guest entered the book.
+    Create,
+    /// The name or password of the guest changed.
+    Update,
+    /// The guest checked out, and is hidden until restored or purged.
+    Delete,
+    /// The guest returned from checking out.
+    Restore,
+    /// The guest was removed from the book for good.
+    Purge,
+    /// The guest logged in.
+    Login,
+    /// A login for the guest was refused.
+    LoginFailed,
+}
+
+/// The `Audit` struct is one entry in the audit trail, recording a single [`Action`] on a single
+/// guest.  Entries are never changed or removed, and outlive the guest they describe.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "sql", derive(sqlx::FromRow))]
+#[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audit {
+    /// The `id` field orders the entries, increasing with each entry recorded.
+    pub id: i64,
+    /// The `recorded_at` field holds when the change was made.
+    pub recorded_at: DateTime<Utc>,
+    /// The `actor` field holds who made the change.
+    pub actor: String,
+    /// The `guest_id` field holds the `id` of the guest changed.
+    pub guest_id: Uuid,
+    /// The `action` field holds the kind of change.
+    pub action: Action,
+    /// The `diff` field maps each field of the guest that changed to its `before` and `after`
+    /// values, `null` where the guest did not exist.  Credentials are replaced with
+    /// [`Audit::REDACTED`].
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub diff: Value,
+    /// The `request_id` field holds the id of the request making the change, if any.
+    pub request_id: Option<String>,
+    /// The `ip` field holds the address of the client making the change, if known.
+    pub ip: Option<String>,
+}
+
+impl Audit {
+    /// The fields of a [`crate::Guest`] holding credentials, never written to the trail.
+    pub const SECRETS: [&'static str; 1] = ["hash"];
+    /// The value recorded in place of a credential.
+    pub const REDACTED: &'static str = "[redacted]";
+
+    /// The `diff` method compares the `before` and `after` states of a guest, returning an object
+    /// holding the `before` and `after` values of each field that differs.  A missing state
+    /// counts as every field being `null`, and credentials are replaced with
+    /// [`Audit::REDACTED`], so a changed password shows only that it changed.
+    #[cfg(feature = "serial")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+    pub fn diff(before: Option<&Guest>, after: Option<&Guest>) -> Polite<Value> {
+        let before = Self::fields(before)?;
+        let after = Self::fields(after)?;
+        let mut keys = before.keys().chain(after.keys()).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        let mut diff = Map::new();
+        for key in keys {
+            let old = before.get(key).unwrap_or(&Value::Null);
+            let new = after.get(key).unwrap_or(&Value::Null);
+            if old != new {
+                diff.insert(
+                    key.clone(),
+                    json!({ "before": Self::redact(key, old), "after": Self::redact(key, new) }),
+                );
+            }
+        }
+        Ok(Value::Object(diff))
+    }
+
+    #[cfg(feature = "serial")]
+    fn fields(guest: Option<&Guest>) -> Polite<Map<String, Value>> {
+        match guest.map(serde_json::to_value).transpose()? {
+            Some(Value::Object(fields)) => Ok(fields),
+            _ => Ok(Map::new()),
+        }
+    }
+
+    #[cfg(feature = "serial")]
+    fn redact(key: &str, value: &Value) -> Value {
+        match Self::SECRETS.contains(&key) && !value.is_null() {
+            true => Value::from(Self::REDACTED),
+            false => value.clone(),
+        }
+    }
+}
+
+/// The `Review` struct holds optional filters and paging for a listing of [`Audit`] entries.
+/// Entries are listed newest first.  To read the next page, pass the `id` of the last entry
+/// received as `cursor`.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serial", serde(default))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Review {
+    /// Only include entries about the guest with this `id`.
+    pub guest_id: Option<Uuid>,
+    /// Only include entries made by this actor.
+    pub actor: Option<String>,
+    /// Only include entries of this kind.
+    #[cfg_attr(feature = "openapi", param(inline))]
+    pub action: Option<Action>,
+    /// Only include entries from the request with this id.
+    pub request_id: Option<String>,
+    /// Only include entries recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only include entries recorded at or before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only include entries older than the entry with this `id`.
+    pub cursor: Option<i64>,
+    /// The largest number of entries to return, [`Review::LIMIT`] if not set.
+    pub limit: Option<i64>,
+}
+
+impl Review {
+    /// The default number of entries in a page.
+    pub const LIMIT: i64 = 50;
+    /// The bounds on the number of entries in a page.
+    pub const LIMITS: std::ops::RangeInclusive<i64> = 1..=500;
+
+    /// Creates a new, empty `Review`.  Modify the fields directly after construction to
+    /// customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `validate` method checks that `limit` falls within [`Review::LIMITS`].  Commits a
+    /// [`FauxPas::Invalid`] if it does not.
+    pub fn validate(&self) -> Polite<()> {
+        match self.limit {
+            Some(limit) if !Self::LIMITS.contains(&limit) => {
+                Err(FauxPas::Invalid(vec![Flaw::new(
+                    "limit",
+                    "range",
+                    &format!(
+                        "Must be between {} and {}.",
+                        Self::LIMITS.start(),
+                        Self::LIMITS.end()
+                    ),
+                )]))
+            }
+            _ => Ok(()),
+        }
+    }
+}
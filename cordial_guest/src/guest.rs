@@ -1,4 +1,6 @@
 //! The `guest` module holds the [`Guest`] struct for managing multiple users in an application.
+use chrono::{DateTime, Utc};
+use unicode_normalization::UnicodeNormalization;
 use uuid::Uuid;
 
 /// The `Guest` struct provides convenience methods around user management.
@@ -6,24 +8,131 @@ use uuid::Uuid;
 #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
 #[cfg_attr(feature = "sql", derive(sqlx::FromRow))]
 #[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[cfg_attr(feature = "valid", derive(validator::Validate))]
+#[cfg_attr(docsrs, doc(cfg(feature = "valid")))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Guest {
-    /// The `id` field holds an identifier unique to the `Guest`.
+    /// The `id` field holds an identifier unique to the `Guest`.  Assigned by the server when a
+    /// guest checks in, so a client may leave it out.
+    #[cfg_attr(feature = "serial", serde(default))]
     pub id: Uuid,
-    /// The `name` field holds the name of the `Guest`.
+    /// The `name` field holds the name of the `Guest`, from 1 to [`Guest::NAME_MAX`] characters
+    /// without control characters or surrounding whitespace.
+    #[cfg_attr(
+        feature = "valid",
+        validate(
+            length(min = 1, max = Guest::NAME_MAX),
+            custom(function = "Guest::printable")
+        )
+    )]
+    #[cfg_attr(feature = "openapi", schema(schema_with = Guest::name_schema))]
     pub name: String,
-    /// The `hash` field holds the hashed password of the `Guest`.
+    /// The `hash` field holds the hashed password of the `Guest`, up to [`Guest::PASS_MAX`]
+    /// characters.
+    #[cfg_attr(
+        feature = "valid",
+        validate(length(max = Guest::PASS_MAX))
+    )]
+    #[cfg_attr(feature = "openapi", schema(schema_with = Guest::hash_schema))]
     pub hash: String,
+    /// The `created_at` field records when the `Guest` registered.  Set by the database on
+    /// insert, and ignored when sent by a client.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub created_at: DateTime<Utc>,
+    /// The `updated_at` field records when the `name` or `hash` of the `Guest` last changed.  Set
+    /// by the database, and ignored when sent by a client.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub updated_at: DateTime<Utc>,
+    /// The `last_login_at` field records the most recent login of the `Guest`, if any.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub last_login_at: Option<DateTime<Utc>>,
+    /// The `deleted_at` field records when the `Guest` checked out, if they have.  Deleted guests
+    /// are hidden from lookups until restored or purged.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Guest {
-    /// Create a new `Guest` from a given `name` and password `pass`.
+    /// The most characters allowed in a `name`.
+    pub const NAME_MAX: u64 = 64;
+    /// The most characters allowed in a password.
+    pub const PASS_MAX: u64 = 1024;
+
+    /// Create a new `Guest` from a given `name` and password `pass`.  The timestamp fields hold
+    /// their default values until the `Guest` is entered into the database.
     pub fn new(name: &str, pass: &str) -> Self {
         let id = uuid::Uuid::new_v4();
         Guest {
             id,
             name: name.to_owned(),
             hash: pass.to_owned(),
+            ..Default::default()
         }
     }
+
+    /// The `fold` method returns the comparison key for a guest `name`: the name normalized to
+    /// NFKC, case folded, and normalized again.  Names with the same key, such as "Alice" and
+    /// "alice", are treated as the same name.
+    pub fn fold(name: &str) -> String {
+        let folded = caseless::default_case_fold_str(&name.nfkc().collect::<String>());
+        folded.nfkc().collect()
+    }
+
+    /// The `key` method returns the comparison key for the `name` of the `Guest`.  See
+    /// [`Guest::fold`].
+    pub fn key(&self) -> String {
+        Self::fold(&self.name)
+    }
+}
+
+#[cfg(feature = "valid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "valid")))]
+impl Guest {
+    /// The `printable` method checks that `name` holds no control characters and does not start
+    /// or end with whitespace, the charset rule on the `name` of a `Guest`.
+    pub fn printable(name: &str) -> Result<(), validator::ValidationError> {
+        if name.chars().any(char::is_control) {
+            return Err(validator::ValidationError::new("charset")
+                .with_message("Must not contain control characters.".into()));
+        }
+        if name.trim() != name {
+            return Err(validator::ValidationError::new("charset")
+                .with_message("Must not start or end with whitespace.".into()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "openapi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+impl Guest {
+    /// The `name_schema` method describes the `name` field of a `Guest` in the OpenAPI document,
+    /// bounded by [`Guest::NAME_MAX`].
+    pub fn name_schema() -> utoipa::openapi::Object {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(format!(
+                "The name of the guest, from 1 to {} characters without control characters or \
+                 surrounding whitespace.",
+                Self::NAME_MAX
+            )))
+            .min_length(Some(1))
+            .max_length(Some(Self::NAME_MAX as usize))
+            .build()
+    }
+
+    /// The `hash_schema` method describes the `hash` field of a `Guest` in the OpenAPI document,
+    /// bounded by [`Guest::PASS_MAX`].
+    pub fn hash_schema() -> utoipa::openapi::Object {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(format!(
+                "The hashed password of the guest, up to {} characters.",
+                Self::PASS_MAX
+            )))
+            .max_length(Some(Self::PASS_MAX as usize))
+            .build()
+    }
 }
@@ -0,0 +1,96 @@
+//! The `inquiry` module holds the [`Inquiry`] struct, describing how to sort and filter a listing
+//! of [`crate::Guest`] entries.
+use chrono::{DateTime, Utc};
+
+/// The `Sort` enum names the [`crate::Guest`] fields available for ordering a listing.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serial", serde(rename_all = "snake_case"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Sort {
+    /// Order by the `name` field.
+    Name,
+    /// Order by the `created_at` field.
+    #[default]
+    CreatedAt,
+    /// Order by the `updated_at` field.
+    UpdatedAt,
+    /// Order by the `last_login_at` field.
+    LastLoginAt,
+}
+
+impl Sort {
+    /// The `column` method returns the name of the database column matching the variant.
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::LastLoginAt => "last_login_at",
+        }
+    }
+}
+
+/// The `Order` enum indicates the direction of a sort.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serial", serde(rename_all = "snake_case"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+    /// Smallest values first.
+    #[default]
+    Asc,
+    /// Largest values first.
+    Desc,
+}
+
+impl Order {
+    /// The `keyword` method returns the SQL keyword matching the variant.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// The `Inquiry` struct holds optional sorting and filtering parameters for a listing of
+/// [`crate::Guest`] entries.  The time bounds are inclusive, and an empty `Inquiry` matches every
+/// guest, ordered by `created_at`.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serial", serde(default))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Inquiry {
+    /// The `sort` field selects the field used to order results.
+    pub sort: Option<Sort>,
+    /// The `order` field selects the direction of the sort.
+    pub order: Option<Order>,
+    /// Only include guests created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only include guests created at or before this time.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only include guests updated at or after this time.
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Only include guests updated at or before this time.
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Only include guests who last logged in at or after this time.
+    pub login_after: Option<DateTime<Utc>>,
+    /// Only include guests who last logged in at or before this time.
+    pub login_before: Option<DateTime<Utc>>,
+}
+
+impl Inquiry {
+    /// Creates a new, empty `Inquiry`.  Modify the fields directly after construction to
+    /// customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -1,3 +1,9 @@
+pub mod audit;
 pub mod guest;
+pub mod inquiry;
+pub mod vitals;
 
+pub use crate::audit::{Action, Audit, Review};
 pub use crate::guest::Guest;
+pub use crate::inquiry::{Inquiry, Order, Sort};
+pub use crate::vitals::{Migrations, Milestone, Pool, Pulse, Vitals};
@@ -0,0 +1,117 @@
+//! The `vitals` module contains the [`Vitals`] struct, a report on the health of the database
+//! behind the server, used to decide whether the server is ready for traffic.  The report is taken
+//! by the `cordial_recall` crate; the types live here so that clients can read it without building
+//! the database stack.
+use std::time::Duration;
+
+/// The `Vitals` struct reports whether the database answers queries, whether its migrations are
+/// current, and how busy the connection pool is.  The `ready` field is `true` only if the
+/// database answered within the timeout and no migration is pending.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vitals {
+    /// The `ready` field summarizes the report, `true` if the server can handle requests.
+    pub ready: bool,
+    /// The `database` field reports on the connection to the database.
+    pub database: Pulse,
+    /// The `migrations` field reports on the schema of the database.
+    pub migrations: Migrations,
+    /// The `pool` field reports on the connection pool, measured before the check.
+    pub pool: Pool,
+}
+
+impl Vitals {
+    /// The default time allowed for the database to answer, two seconds.
+    pub const TIMEOUT: Duration = Duration::from_secs(2);
+}
+
+/// The `Pulse` struct reports on the connection to the database.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pulse {
+    /// Whether the database answered within the timeout.
+    pub reachable: bool,
+    /// The time taken by the check in milliseconds.
+    pub latency_ms: u64,
+    /// The version reported by the database, if reachable.
+    pub version: Option<String>,
+    /// The reason the check failed, if unreachable.
+    pub error: Option<String>,
+}
+
+/// The `Migrations` struct reports on the schema of the database.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migrations {
+    /// Whether every known migration has been applied, `false` if the database is unreachable.
+    pub current: bool,
+    /// The number of known migrations applied.
+    pub applied: usize,
+    /// The known migrations not yet applied, empty if the database is unreachable.
+    pub pending: Vec<Milestone>,
+}
+
+impl From<Option<Vec<Milestone>>> for Migrations {
+    fn from(milestones: Option<Vec<Milestone>>) -> Self {
+        match milestones {
+            Some(milestones) => {
+                let (applied, pending): (Vec<_>, Vec<_>) =
+                    milestones.into_iter().partition(|m| m.applied);
+                Self {
+                    current: pending.is_empty(),
+                    applied: applied.len(),
+                    pending,
+                }
+            }
+            None => Self {
+                current: false,
+                applied: 0,
+                pending: Vec::new(),
+            },
+        }
+    }
+}
+
+/// The `Milestone` struct describes one migration known to the server and whether the database
+/// has applied it.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    /// The version of the migration, taken from the date prefix of its file name.
+    pub version: i64,
+    /// The description of the migration, taken from the rest of its file name.
+    pub description: String,
+    /// Whether the migration has been applied successfully.
+    pub applied: bool,
+}
+
+/// The `Pool` struct reports on the connection pool.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pool {
+    /// The number of open connections.
+    pub size: u32,
+    /// The number of open connections not in use.
+    pub idle: usize,
+    /// The number of connections in use.
+    pub busy: u32,
+    /// The largest number of connections the pool will open.
+    pub max: u32,
+    /// The share of `max` connections in use, from zero to one.
+    pub saturation: f64,
+}
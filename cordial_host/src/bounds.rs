@@ -0,0 +1,87 @@
+//! The `bounds` module contains the [`Bounds`] struct, holding the largest request body and the
+//! longest request the [`crate::Host`] accepts.
+use axum::extract::DefaultBodyLimit;
+use axum::http::StatusCode;
+use axum::Router;
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
+
+/// The `Bounds` struct holds the size limit on request bodies and the time limit on answering a
+/// request.  A larger body is refused with a `413 Payload Too Large`, and a request still running
+/// at the time limit is cancelled with a `503 Service Unavailable`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bounds {
+    /// The `body` field holds the largest request body accepted, in bytes.
+    pub body: usize,
+    /// The `timeout` field holds the time allowed to answer each request.
+    pub timeout: Duration,
+}
+
+impl Bounds {
+    /// The default body limit of one mebibyte.
+    pub const BODY: usize = 1024 * 1024;
+    /// The default timeout of thirty seconds.
+    pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Creates `Bounds` from the default method.  Modify the fields directly after construction
+    /// to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `from_env` method creates `Bounds` from the optional variables `HOST_BODY_LIMIT_BYTES`
+    /// and `HOST_TIMEOUT_SECS`, using the defaults for those not set.  Commits a [`FauxPas`] if a
+    /// value is not a whole number.
+    pub fn from_env() -> Polite<Self> {
+        dotenvy::dotenv().ok();
+        let mut bounds = Self::default();
+        if let Ok(body) = std::env::var("HOST_BODY_LIMIT_BYTES") {
+            bounds.body = body.trim().parse()?;
+        }
+        if let Ok(timeout) = std::env::var("HOST_TIMEOUT_SECS") {
+            bounds.timeout = Duration::from_secs(timeout.trim().parse()?);
+        }
+        Ok(bounds)
+    }
+
+    /// The `validate` method checks that the body limit and timeout are both nonzero.  Commits a
+    /// [`FauxPas::Invalid`] listing each [`Flaw`] found.
+    pub fn validate(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        if self.body == 0 {
+            flaws.push(Flaw::new("body", "range", "Must be at least 1 byte."));
+        }
+        if self.timeout.is_zero() {
+            flaws.push(Flaw::new("timeout", "range", "Must be longer than zero."));
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+
+    /// The `bound` method returns `router` behind the body size and time limits.  The body limit
+    /// replaces the default limit of the axum extractors.
+    pub fn bound(&self, router: Router) -> Router {
+        router
+            .layer(DefaultBodyLimit::disable())
+            .layer(RequestBodyLimitLayer::new(self.body))
+            .layer(TimeoutLayer::with_status_code(
+                StatusCode::SERVICE_UNAVAILABLE,
+                self.timeout,
+            ))
+    }
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            body: Self::BODY,
+            timeout: Self::TIMEOUT,
+        }
+    }
+}
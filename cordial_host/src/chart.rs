@@ -0,0 +1,61 @@
+//! The `chart` module contains the [`Chart`] struct, the OpenAPI description of every route the
+//! [`crate::Host`] serves: the routes of [`counsel::Charter`] along with those the host serves
+//! itself.
+use axum::response::IntoResponse;
+use axum::Json;
+use counsel::Charter;
+use utoipa::OpenApi;
+
+/// The `Chart` struct describes the routes the [`crate::Host`] serves itself, and merges them
+/// with the [`Charter`] in [`Chart::document`].
+#[derive(Debug, Copy, Clone, Default, OpenApi)]
+#[openapi(paths(openapi, crate::door::login, crate::tally::scrape))]
+pub struct Chart;
+
+impl Chart {
+    /// The `document` method returns the OpenAPI document describing every route of the
+    /// [`crate::Host`], with the `/docs` page when the `redoc` feature is enabled.
+    pub fn document() -> utoipa::openapi::OpenApi {
+        let mut document = Charter::openapi();
+        document.merge(Self::openapi());
+        #[cfg(feature = "redoc")]
+        document.merge(Pages::openapi());
+        document
+    }
+}
+
+/// The `Pages` struct describes the `/docs` page.
+#[cfg(feature = "redoc")]
+#[derive(OpenApi)]
+#[openapi(paths(docs))]
+struct Pages;
+
+/// The `openapi` method returns the OpenAPI description of every route.  See [`Chart`].
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "docs",
+    summary = "Returns this OpenAPI document.",
+    responses(
+        (status = 200, description = "The OpenAPI document.", body = Object),
+    )
+)]
+pub async fn openapi() -> impl IntoResponse {
+    Json(Chart::document())
+}
+
+/// The `docs` method returns a Redoc page rendering the OpenAPI description of every route.
+#[cfg(feature = "redoc")]
+#[utoipa::path(
+    get,
+    path = "/docs",
+    tag = "docs",
+    summary = "Renders this OpenAPI document as a page.",
+    responses(
+        (status = 200, description = "The rendered document.", body = String, content_type = "text/html"),
+    )
+)]
+pub async fn docs() -> impl IntoResponse {
+    use utoipa_redoc::Redoc;
+    axum::response::Html(Redoc::new(Chart::document()).to_html())
+}
@@ -0,0 +1,135 @@
+//! The `courier` module contains the [`Courier`] struct, holding the settings for exporting the
+//! traces of the [`crate::Host`] to an OpenTelemetry collector over OTLP.
+use axum::http::Uri;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The `Courier` struct holds whether traces are exported, the collector that receives them, and
+/// the service name they are reported under.  Traces are sent as protobuf over HTTP to the
+/// `/v1/traces` path of the `endpoint`.  Export is disabled by default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Courier {
+    /// The `enabled` field turns trace export on.
+    pub enabled: bool,
+    /// The `endpoint` field holds the base URL of the collector, such as
+    /// `http://127.0.0.1:4318`.
+    pub endpoint: String,
+    /// The `service` field holds the `service.name` reported with every span.
+    pub service: String,
+    /// The `timeout` field holds the time allowed for each export to the collector.
+    pub timeout: Duration,
+}
+
+impl Courier {
+    /// The default endpoint, a collector on the local machine.
+    pub const ENDPOINT: &'static str = "http://127.0.0.1:4318";
+    /// The path below the `endpoint` receiving traces.
+    pub const TRACES: &'static str = "/v1/traces";
+    /// The default service name.
+    pub const SERVICE: &'static str = "cordial";
+    /// The default export timeout of ten seconds.
+    pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Creates a `Courier` from the default method.  Modify the fields directly after
+    /// construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `from_env` method creates a `Courier` from the optional variables `OTLP_ENABLED`
+    /// (`true` or `false`), `OTLP_ENDPOINT`, `OTLP_SERVICE` and `OTLP_TIMEOUT_SECS`, using the
+    /// defaults for those not set.  Commits a [`FauxPas`] if a value cannot be parsed.
+    pub fn from_env() -> Polite<Self> {
+        dotenvy::dotenv().ok();
+        let mut courier = Self::default();
+        if let Ok(enabled) = std::env::var("OTLP_ENABLED") {
+            courier.enabled = enabled.trim().parse().map_err(|_| {
+                FauxPas::Invalid(vec![Flaw::new(
+                    "enabled",
+                    "format",
+                    &format!("Must be true or false, found {}.", enabled),
+                )])
+            })?;
+        }
+        if let Ok(endpoint) = std::env::var("OTLP_ENDPOINT") {
+            courier.endpoint = endpoint.trim().to_string();
+        }
+        if let Ok(service) = std::env::var("OTLP_SERVICE") {
+            courier.service = service.trim().to_string();
+        }
+        if let Ok(timeout) = std::env::var("OTLP_TIMEOUT_SECS") {
+            courier.timeout = Duration::from_secs(timeout.trim().parse()?);
+        }
+        Ok(courier)
+    }
+
+    /// The `validate` method checks that the `endpoint` is an absolute `http` or `https` URL and
+    /// that the `service` is named.  Commits a [`FauxPas::Invalid`] listing each [`Flaw`] found.
+    pub fn validate(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        let endpoint = self.endpoint.parse::<Uri>();
+        let absolute = endpoint.is_ok_and(|uri| {
+            matches!(uri.scheme_str(), Some("http" | "https")) && uri.authority().is_some()
+        });
+        if !absolute {
+            flaws.push(Flaw::new(
+                "endpoint",
+                "format",
+                &format!("Must be an http or https URL, found {}.", self.endpoint),
+            ));
+        }
+        if self.service.trim().is_empty() {
+            flaws.push(Flaw::new("service", "required", "Must not be empty."));
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+
+    /// The `dispatch` method builds the tracer provider exporting spans to the collector, or
+    /// returns `None` if export is disabled.  Spans are exported in batches from a background
+    /// thread; call [`SdkTracerProvider::shutdown`] before exiting to send the last batch.
+    /// Commits a [`FauxPas::Invalid`] if the settings fail [`Courier::validate`], or a
+    /// [`FauxPas::Otlp`] if the exporter cannot be built.
+    pub fn dispatch(&self) -> Polite<Option<SdkTracerProvider>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        self.validate()?;
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!(
+                "{}{}",
+                self.endpoint.trim_end_matches('/'),
+                Self::TRACES
+            ))
+            .with_timeout(self.timeout)
+            .build()?;
+        let resource = Resource::builder()
+            .with_service_name(self.service.clone())
+            .build();
+        Ok(Some(
+            SdkTracerProvider::builder()
+                .with_batch_exporter(exporter)
+                .with_resource(resource)
+                .build(),
+        ))
+    }
+}
+
+impl Default for Courier {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: Self::ENDPOINT.to_string(),
+            service: Self::SERVICE.to_string(),
+            timeout: Self::TIMEOUT,
+        }
+    }
+}
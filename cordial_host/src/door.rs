@@ -0,0 +1,400 @@
+//! The `door` module contains the [`Door`] struct, which enforces the rate limits and lockouts
+//! described by a [`Doorman`], keeping its token buckets and failed login counts in memory, and
+//! the [`login`] route the lockouts guard.
+use crate::doorman::{Doorman, Group, Rate};
+use crate::host::Host;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use cordial_guest::Guest;
+use counsel::{Attended, Counsel};
+use polite::Problem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{info, trace, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The `Caller` enum names who a token bucket belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Caller {
+    Ip(IpAddr),
+    Account(String),
+}
+
+/// The `Bucket` struct holds the tokens left for one caller in one [`Group`], as of `updated`.
+#[derive(Debug, Copy, Clone)]
+struct Bucket {
+    rate: Rate,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rate: Rate, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate.burst as f64,
+            updated: now,
+        }
+    }
+
+    /// Adds the tokens refilled since the last update.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate.refill()).min(self.rate.burst as f64);
+        self.updated = now;
+    }
+
+    /// The time until a whole token is available.
+    fn wait(&self) -> Duration {
+        Duration::from_secs_f64((1.0 - self.tokens).max(0.0) / self.rate.refill())
+    }
+
+    /// The time until the bucket is full.
+    fn reset(&self) -> Duration {
+        Duration::from_secs_f64((self.rate.burst as f64 - self.tokens) / self.rate.refill())
+    }
+
+    fn full(&self) -> bool {
+        self.tokens >= self.rate.burst as f64
+    }
+
+    fn quota(&self) -> Quota {
+        Quota {
+            limit: self.rate.burst,
+            remaining: self.tokens.floor() as u32,
+            reset: self.reset(),
+        }
+    }
+}
+
+/// The `Strikes` struct counts the failed logins of a guest in a row.
+#[derive(Debug, Copy, Clone)]
+struct Strikes {
+    failures: u32,
+    last: Instant,
+    until: Option<Instant>,
+}
+
+/// The `Quota` struct reports the state of the tightest token bucket applied to a request, sent
+/// back in the rate limit headers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Quota {
+    /// The `limit` field holds the number of requests the bucket holds when full.
+    pub limit: u32,
+    /// The `remaining` field holds the number of requests left in the bucket.
+    pub remaining: u32,
+    /// The `reset` field holds the time until the bucket is full again.
+    pub reset: Duration,
+}
+
+impl Quota {
+    /// Writes the quota to the rate limit headers of `headers`.
+    fn stamp(&self, headers: &mut HeaderMap) {
+        headers.insert(Door::LIMIT, HeaderValue::from(self.limit));
+        headers.insert(Door::REMAINING, HeaderValue::from(self.remaining));
+        headers.insert(Door::RESET, HeaderValue::from(Door::seconds(self.reset)));
+    }
+}
+
+/// The `Door` struct admits requests to the [`crate::Host`] within the limits of its [`Doorman`].
+/// Each client address draws from its own token bucket in each [`Group`] of routes, and so does
+/// each caller named in the `x-actor` header where an account rate is set.  The header is not
+/// authenticated, so account buckets are advisory: a caller can dodge them by changing it.  A
+/// request that would overdraw any bucket is refused with a `429 Too Many Requests` and a
+/// `Retry-After` header, and every limited response reports the tightest bucket in the
+/// `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers.  The `Door` also
+/// locks guests out of [`login`] after repeated failures.  Clones share the same buckets and
+/// strikes.  Client addresses are only known when the routes are served with
+/// [`crate::Host::serve_until`].
+#[derive(Debug, Clone, Default)]
+pub struct Door {
+    doorman: Arc<Doorman>,
+    buckets: Arc<Mutex<HashMap<(Group, Caller), Bucket>>>,
+    strikes: Arc<Mutex<HashMap<Uuid, Strikes>>>,
+}
+
+impl Door {
+    /// The header holding the number of requests a full bucket allows.
+    pub const LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+    /// The header holding the number of requests left in the bucket.
+    pub const REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+    /// The header holding the seconds until the bucket is full again.
+    pub const RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+    /// The number of entries kept before full buckets and expired strikes are dropped.
+    pub const CAPACITY: usize = 10_000;
+
+    /// Creates a `Door` enforcing `doorman`, with every bucket full and no strikes.
+    pub fn new(doorman: Doorman) -> Self {
+        Self {
+            doorman: Arc::new(doorman),
+            ..Default::default()
+        }
+    }
+
+    /// The `doorman` method returns the settings enforced by the `Door`.
+    pub fn doorman(&self) -> &Doorman {
+        &self.doorman
+    }
+
+    /// The `admit` middleware draws a token for the request from the bucket of its address and
+    /// of its caller in its [`Group`], passing it on if both have one and refusing it otherwise.
+    pub async fn admit(State(door): State<Door>, request: Request, next: Next) -> Response {
+        if !door.doorman.enabled {
+            return next.run(request).await;
+        }
+        let Some(group) = Group::of(request.uri().path()) else {
+            return next.run(request).await;
+        };
+        let allowance = door.doorman.allowance(group);
+        let mut callers = Vec::new();
+        if let Some(rate) = allowance.ip {
+            if let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+                callers.push((Caller::Ip(addr.ip()), rate));
+            }
+        }
+        if let Some(rate) = allowance.account {
+            let actor = request
+                .headers()
+                .get(Attended::ACTOR)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|actor| !actor.is_empty());
+            if let Some(actor) = actor {
+                callers.push((Caller::Account(actor.to_string()), rate));
+            }
+        }
+        match door.take(group, &callers) {
+            None => next.run(request).await,
+            Some(Ok(quota)) => {
+                let mut response = next.run(request).await;
+                quota.stamp(response.headers_mut());
+                response
+            }
+            Some(Err(quota)) => {
+                trace!("Refusing {:?} in {}.", callers, group.name());
+                let mut response = Self::turn_away(quota.reset);
+                quota.stamp(response.headers_mut());
+                response
+            }
+        }
+    }
+
+    /// Draws a token from the bucket of each of `callers` in `group`, all or none.  Returns the
+    /// quota of the tightest bucket, or an error holding a quota whose `reset` is the wait before
+    /// every bucket has a token, or `None` if no bucket applies.
+    fn take(&self, group: Group, callers: &[(Caller, Rate)]) -> Option<Result<Quota, Quota>> {
+        if callers.is_empty() {
+            return None;
+        }
+        let now = Instant::now();
+        let mut buckets = Self::lock(&self.buckets);
+        if buckets.len() >= Self::CAPACITY {
+            buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                !bucket.full()
+            });
+        }
+        let mut drawn = Vec::with_capacity(callers.len());
+        for (caller, rate) in callers {
+            let bucket = buckets
+                .entry((group, caller.clone()))
+                .or_insert_with(|| Bucket::new(*rate, now));
+            bucket.rate = *rate;
+            bucket.refill(now);
+            drawn.push((group, caller.clone()));
+        }
+        let refused = drawn
+            .iter()
+            .filter_map(|key| buckets.get(key))
+            .filter(|bucket| bucket.tokens < 1.0)
+            .map(|bucket| bucket.wait())
+            .max();
+        if let Some(wait) = refused {
+            let limit = callers
+                .iter()
+                .map(|(_, rate)| rate.burst)
+                .min()
+                .unwrap_or(0);
+            return Some(Err(Quota {
+                limit,
+                remaining: 0,
+                reset: wait,
+            }));
+        }
+        let mut tightest: Option<Quota> = None;
+        for key in &drawn {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+                let quota = bucket.quota();
+                if tightest.is_none_or(|t| quota.remaining < t.remaining) {
+                    tightest = Some(quota);
+                }
+            }
+        }
+        tightest.map(Ok)
+    }
+
+    /// The `strike` method counts a failed login for the guest with `id`, returning how long
+    /// they are locked out for, if at all.  Failures are forgotten once the longest lockout has
+    /// passed since the last one.  See [`crate::Lockout`].
+    pub fn strike(&self, id: Uuid) -> Option<Duration> {
+        let now = Instant::now();
+        let lockout = self.doorman.lockout;
+        let mut strikes = Self::lock(&self.strikes);
+        if strikes.len() >= Self::CAPACITY {
+            strikes.retain(|_, s| now.saturating_duration_since(s.last) < lockout.max);
+        }
+        let entry = strikes.entry(id).or_insert(Strikes {
+            failures: 0,
+            last: now,
+            until: None,
+        });
+        if now.saturating_duration_since(entry.last) >= lockout.max {
+            entry.failures = 0;
+        }
+        entry.failures = entry.failures.saturating_add(1);
+        entry.last = now;
+        let penalty = lockout.penalty(entry.failures);
+        entry.until = penalty.map(|penalty| now + penalty);
+        if let Some(penalty) = penalty {
+            warn!(
+                "Locking out guest {} for {:?} after {} failed logins.",
+                id, penalty, entry.failures
+            );
+        }
+        penalty
+    }
+
+    /// The `locked` method returns how much longer the guest with `id` is locked out for, or
+    /// `None` if they may log in.
+    pub fn locked(&self, id: Uuid) -> Option<Duration> {
+        let now = Instant::now();
+        Self::lock(&self.strikes)
+            .get(&id)
+            .and_then(|strikes| strikes.until)
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// The `clear` method forgets the failed logins of the guest with `id`, called after a
+    /// successful login.
+    pub fn clear(&self, id: Uuid) {
+        Self::lock(&self.strikes).remove(&id);
+    }
+
+    /// The `turn_away` method returns a `429 Too Many Requests` asking the caller to wait for
+    /// `wait` before trying again, for refusing a rate limited request or a locked out login.
+    /// The body is a [`Problem`], as for the other refusals; see [`Counsel::problem`].
+    pub fn turn_away(wait: Duration) -> Response {
+        let seconds = Self::seconds(wait);
+        let status = StatusCode::TOO_MANY_REQUESTS;
+        let mut response = Counsel::problem(Problem::new(
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default(),
+            &format!("Too many requests, retry in {} seconds.", seconds),
+        ));
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        response
+    }
+
+    /// The `matches` method compares the stored `hash` of a guest with the `offered` one, taking
+    /// the same time wherever they differ.
+    fn matches(hash: &str, offered: &str) -> bool {
+        hash.len() == offered.len()
+            && hash
+                .bytes()
+                .zip(offered.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// Rounds `wait` up to whole seconds.
+    fn seconds(wait: Duration) -> u64 {
+        wait.as_secs_f64().ceil() as u64
+    }
+
+    /// Locks `map`, recovering it if a thread panicked while holding it.
+    fn lock<K, V>(map: &Mutex<HashMap<K, V>>) -> MutexGuard<'_, HashMap<K, V>> {
+        map.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The `Credentials` struct holds the name and hashed password a guest logs in with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Credentials {
+    /// The `name` field holds the name of the guest, ignoring case and Unicode compatibility
+    /// differences.
+    pub name: String,
+    /// The `hash` field holds the hashed password of the guest.
+    pub hash: String,
+}
+
+/// The `login` method logs in the guest named in the [`Credentials`], stamping their
+/// `last_login_at` and returning the updated [`Guest`].  A wrong `hash` counts a strike against
+/// the guest on the [`Door`] of the host and is recorded in the audit trail.  Once the strikes
+/// reach the [`crate::Lockout`] threshold, attempts are refused with a `429 Too Many Requests`
+/// and a `Retry-After` header until the lockout passes, even with the right `hash`.  A successful
+/// login clears the strikes.  An unknown name and a wrong `hash` are refused alike with a `401
+/// Unauthorized`.
+#[utoipa::path(
+    post,
+    path = "/guests/login",
+    tag = "guests",
+    summary = "Logs a guest in, locking them out after repeated failures.",
+    request_body = Credentials,
+    responses(
+        (status = 200, description = "The guest, with the login stamped.", body = Guest),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "The name or hash is wrong.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The body could not be read.", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "The guest is locked out, with the seconds to wait in the Retry-After header.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn login(State(host): State<Host>, Json(credentials): Json<Credentials>) -> Response {
+    info!("Logging in {}.", &credentials.name);
+    let guest = match host.recall.find_by_name(&credentials.name).await {
+        Ok(Some(guest)) => guest,
+        Ok(None) => return unauthorized(),
+        Err(e) => return Counsel::refuse(e),
+    };
+    if let Some(wait) = host.door.locked(guest.id) {
+        trace!("Guest {} is locked out.", guest.id);
+        if let Err(e) = host.recall.refuse_login(guest.id).await {
+            return Counsel::refuse(e);
+        }
+        return Door::turn_away(wait);
+    }
+    if !Door::matches(&guest.hash, &credentials.hash) {
+        if let Err(e) = host.recall.refuse_login(guest.id).await {
+            return Counsel::refuse(e);
+        }
+        return match host.door.strike(guest.id) {
+            Some(wait) => Door::turn_away(wait),
+            None => unauthorized(),
+        };
+    }
+    host.door.clear(guest.id);
+    match host.recall.record_login(guest.id).await {
+        Ok(guest) => (StatusCode::OK, Json(guest)).into_response(),
+        Err(e) => Counsel::refuse(e),
+    }
+}
+
+/// Refuses a login with a `401 Unauthorized`, without saying whether the name or hash was wrong.
+fn unauthorized() -> Response {
+    let status = StatusCode::UNAUTHORIZED;
+    Counsel::problem(Problem::new(
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        "The name or hash is wrong.",
+    ))
+}
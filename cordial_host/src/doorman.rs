@@ -0,0 +1,320 @@
+//! The `doorman` module contains the [`Doorman`] struct, holding the rate limits applied to each
+//! group of routes by the [`crate::Door`], and the [`Lockout`] applied to guests after repeated
+//! failed logins.
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The `Group` enum sorts the routes of the host for rate limiting, following the tags of
+/// [`counsel::Charter`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Group {
+    /// Probes of the server and its database, and metrics.
+    Health,
+    /// Descriptions of the API.
+    Docs,
+    /// Entering, finding, updating and checking out guests.
+    Guests,
+    /// Restoring and purging guests, and the audit trail.
+    Admin,
+    /// Improvised names and passwords.
+    Improv,
+}
+
+impl Group {
+    /// Every group, in order.
+    pub const ALL: [Group; 5] = [
+        Group::Health,
+        Group::Docs,
+        Group::Guests,
+        Group::Admin,
+        Group::Improv,
+    ];
+
+    /// The `of` method returns the group of the route at `path`, or `None` for paths outside
+    /// every group.
+    pub fn of(path: &str) -> Option<Self> {
+        let first = path.trim_start_matches('/').split('/').next()?;
+        match first {
+            "health" | "book" | "metrics" => Some(Self::Health),
+            "openapi.json" | "docs" => Some(Self::Docs),
+            "guests" => Some(Self::Guests),
+            "admin" => Some(Self::Admin),
+            "improv" => Some(Self::Improv),
+            _ => None,
+        }
+    }
+
+    /// The `name` method returns the name of the group, as used in settings.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Health => "health",
+            Self::Docs => "docs",
+            Self::Guests => "guests",
+            Self::Admin => "admin",
+            Self::Improv => "improv",
+        }
+    }
+}
+
+/// The `Rate` struct describes a token bucket holding up to `burst` requests, refilled evenly so
+/// that it fills from empty over each `period`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rate {
+    /// The `burst` field holds the number of requests allowed at once.
+    pub burst: u32,
+    /// The `period` field holds the time taken to refill the bucket from empty.
+    pub period: Duration,
+}
+
+impl Rate {
+    /// Creates a `Rate` allowing `burst` requests every `period`.
+    pub const fn new(burst: u32, period: Duration) -> Self {
+        Self { burst, period }
+    }
+
+    /// The `parse` method reads a rate written as `burst/seconds`, such as `60/60`, or `off` for
+    /// no limit, naming `field` in the [`Flaw`] if it cannot be read.
+    pub fn parse(field: &str, value: &str) -> Polite<Option<Self>> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        let rate = value.split_once('/').and_then(|(burst, secs)| {
+            let burst = burst.trim().parse().ok()?;
+            let secs = secs.trim().parse().ok()?;
+            Some(Self::new(burst, Duration::from_secs(secs)))
+        });
+        match rate {
+            Some(rate) => Ok(Some(rate)),
+            None => Err(FauxPas::Invalid(vec![Flaw::new(
+                field,
+                "format",
+                &format!("Must be burst/seconds or off, found {}.", value),
+            )])),
+        }
+    }
+
+    /// The `refill` method returns the number of requests added to the bucket each second.
+    pub fn refill(&self) -> f64 {
+        self.burst as f64 / self.period.as_secs_f64()
+    }
+
+    fn flaws(&self, field: &str) -> Vec<Flaw> {
+        let mut flaws = Vec::new();
+        if self.burst == 0 {
+            flaws.push(Flaw::new(field, "range", "The burst must be at least 1."));
+        }
+        if self.period.is_zero() {
+            flaws.push(Flaw::new(
+                field,
+                "range",
+                "The period must be at least 1 second.",
+            ));
+        }
+        flaws
+    }
+}
+
+/// The `Allowance` struct holds the rates applied to one [`Group`] of routes.  A request must
+/// fit within each rate that applies to it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Allowance {
+    /// The `ip` field holds the rate for each client address, if limited.
+    pub ip: Option<Rate>,
+    /// The `account` field holds the rate for each caller named in the `x-actor` header, if
+    /// limited.  Requests without the header are only limited by address.  The header is not
+    /// authenticated, so the limit is advisory and off by default.
+    pub account: Option<Rate>,
+}
+
+impl Allowance {
+    /// Creates an `Allowance` limiting each address to `rate`, with no account limit.
+    pub fn new(rate: Rate) -> Self {
+        Self {
+            ip: Some(rate),
+            account: None,
+        }
+    }
+}
+
+/// The `Lockout` struct holds how a guest is locked out after repeated failed logins.  Once a
+/// guest has failed `threshold` times in a row they are locked out for `base`, doubling with each
+/// further failure up to `max`.  A successful login clears the count.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lockout {
+    /// The `threshold` field holds the number of failures in a row that starts the first lockout.
+    pub threshold: u32,
+    /// The `base` field holds the length of the first lockout.
+    pub base: Duration,
+    /// The `max` field holds the longest lockout.
+    pub max: Duration,
+}
+
+impl Lockout {
+    /// The default number of failures allowed.
+    pub const THRESHOLD: u32 = 5;
+    /// The default length of the first lockout.
+    pub const BASE: Duration = Duration::from_secs(1);
+    /// The default longest lockout of fifteen minutes.
+    pub const MAX: Duration = Duration::from_secs(900);
+
+    /// The `penalty` method returns how long a guest is locked out after `failures` failed logins
+    /// in a row, or `None` if they are not locked out.
+    pub fn penalty(&self, failures: u32) -> Option<Duration> {
+        let over = failures.checked_sub(self.threshold)?;
+        let factor = 2u32.checked_pow(over).unwrap_or(u32::MAX);
+        Some(self.base.saturating_mul(factor).min(self.max))
+    }
+}
+
+impl Default for Lockout {
+    fn default() -> Self {
+        Self {
+            threshold: Self::THRESHOLD,
+            base: Self::BASE,
+            max: Self::MAX,
+        }
+    }
+}
+
+/// The `Doorman` struct holds whether requests are rate limited, the [`Allowance`] of each
+/// [`Group`] of routes, and the [`Lockout`] for failed logins.  Groups without an allowance are
+/// not limited.  By default the health and docs routes are open, and the others limited by
+/// address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Doorman {
+    /// The `enabled` field turns rate limiting on.  Lockouts apply regardless.
+    pub enabled: bool,
+    /// The `groups` field holds the allowance of each group of routes.
+    pub groups: BTreeMap<Group, Allowance>,
+    /// The `lockout` field holds the lockout applied after failed logins.
+    pub lockout: Lockout,
+}
+
+impl Doorman {
+    /// The default rate of the guest routes.
+    pub const GUESTS: Rate = Rate::new(120, Duration::from_secs(60));
+    /// The default rate of the admin routes.
+    pub const ADMIN: Rate = Rate::new(30, Duration::from_secs(60));
+    /// The default rate of the improv routes.
+    pub const IMPROV: Rate = Rate::new(60, Duration::from_secs(60));
+
+    /// Creates a `Doorman` from the default method.  Modify the fields directly after
+    /// construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `from_env` method creates a `Doorman` from the optional variables `THROTTLE_ENABLED`
+    /// (`true` or `false`), `THROTTLE_<GROUP>_IP` and `THROTTLE_<GROUP>_ACCOUNT` for each
+    /// [`Group`], such as `THROTTLE_IMPROV_IP=60/60`, along with `LOCKOUT_THRESHOLD`,
+    /// `LOCKOUT_BASE_SECS` and `LOCKOUT_MAX_SECS`, using the defaults for those not set.  Rates
+    /// are written as described in [`Rate::parse`].  Commits a [`FauxPas`] if a value cannot be
+    /// parsed.
+    pub fn from_env() -> Polite<Self> {
+        dotenvy::dotenv().ok();
+        let mut doorman = Self::default();
+        if let Ok(enabled) = std::env::var("THROTTLE_ENABLED") {
+            doorman.enabled = enabled.trim().parse().map_err(|_| {
+                FauxPas::Invalid(vec![Flaw::new(
+                    "enabled",
+                    "format",
+                    &format!("Must be true or false, found {}.", enabled),
+                )])
+            })?;
+        }
+        for group in Group::ALL {
+            let name = group.name();
+            let mut allowance = doorman.allowance(group);
+            let var = format!("THROTTLE_{}_IP", name.to_uppercase());
+            if let Ok(rate) = std::env::var(&var) {
+                allowance.ip = Rate::parse(&format!("groups.{}.ip", name), &rate)?;
+            }
+            let var = format!("THROTTLE_{}_ACCOUNT", name.to_uppercase());
+            if let Ok(rate) = std::env::var(&var) {
+                allowance.account = Rate::parse(&format!("groups.{}.account", name), &rate)?;
+            }
+            match allowance == Allowance::default() {
+                true => doorman.groups.remove(&group),
+                false => doorman.groups.insert(group, allowance),
+            };
+        }
+        if let Ok(threshold) = std::env::var("LOCKOUT_THRESHOLD") {
+            doorman.lockout.threshold = threshold.trim().parse()?;
+        }
+        if let Ok(base) = std::env::var("LOCKOUT_BASE_SECS") {
+            doorman.lockout.base = Duration::from_secs(base.trim().parse()?);
+        }
+        if let Ok(max) = std::env::var("LOCKOUT_MAX_SECS") {
+            doorman.lockout.max = Duration::from_secs(max.trim().parse()?);
+        }
+        Ok(doorman)
+    }
+
+    /// The `allowance` method returns the [`Allowance`] of `group`, unlimited if none is set.
+    pub fn allowance(&self, group: Group) -> Allowance {
+        self.groups.get(&group).copied().unwrap_or_default()
+    }
+
+    /// The `validate` method checks that each rate allows at least one request over a period of
+    /// at least one second, and that the lockout starts after at least one failure and grows from
+    /// a nonzero `base` to a `max` no shorter.  Commits a [`FauxPas::Invalid`] listing each
+    /// [`Flaw`] found.
+    pub fn validate(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        for (group, allowance) in &self.groups {
+            if let Some(rate) = allowance.ip {
+                flaws.extend(rate.flaws(&format!("groups.{}.ip", group.name())));
+            }
+            if let Some(rate) = allowance.account {
+                flaws.extend(rate.flaws(&format!("groups.{}.account", group.name())));
+            }
+        }
+        if self.lockout.threshold == 0 {
+            flaws.push(Flaw::new(
+                "lockout.threshold",
+                "range",
+                "Must be at least 1.",
+            ));
+        }
+        if self.lockout.base.is_zero() {
+            flaws.push(Flaw::new(
+                "lockout.base",
+                "range",
+                "Must be at least 1 second.",
+            ));
+        }
+        if self.lockout.max < self.lockout.base {
+            flaws.push(Flaw::new(
+                "lockout.max",
+                "range",
+                "Must not be shorter than the base.",
+            ));
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+}
+
+impl Default for Doorman {
+    fn default() -> Self {
+        let groups = [
+            (Group::Guests, Allowance::new(Self::GUESTS)),
+            (Group::Admin, Allowance::new(Self::ADMIN)),
+            (Group::Improv, Allowance::new(Self::IMPROV)),
+        ];
+        Self {
+            enabled: true,
+            groups: BTreeMap::from(groups),
+            lockout: Lockout::default(),
+        }
+    }
+}
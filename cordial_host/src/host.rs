@@ -1,20 +1,54 @@
 //! The `host` crate contains the [`Host`] struct, with methods for managing [`Guest`] needs.
-use axum::routing::get;
+use crate::bounds::Bounds;
+use crate::chart;
+use crate::door::{self, Door};
+use crate::doorman::Doorman;
+use crate::tally::{self, Tally};
+use crate::usher::Usher;
+use crate::venue::Venue;
+use crate::welcome::Welcome;
+use axum::middleware;
+use axum::routing::{delete, get, post};
 use axum::Router;
 use cordial_posture::Posture;
 use cordial_recall::Recall;
-use counsel::Counsel;
+use counsel::route;
 use polite::Polite;
 use secrecy::ExposeSecret;
-use tracing::info;
+use std::future::{Future, IntoFuture};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
 
 #[derive(Debug, Clone)]
 pub struct Host {
     pub recall: Recall,
     pub posture: Posture,
+    /// The `door` field holds the rate limits and login lockouts applied by [`Host::bearing`].
+    pub door: Door,
+    /// The `bounds` field holds the body size and time limits applied by [`Host::bearing`].
+    pub bounds: Bounds,
 }
 
 impl Host {
+    /// Creates a `Host` serving the database described by `posture`, leaving its contents in
+    /// place.  The connection pool opens lazily.
+    pub fn new(posture: Posture) -> Self {
+        let recall = Recall::from(&posture);
+        let door = Door::default();
+        let bounds = Bounds::default();
+        Self {
+            recall,
+            posture,
+            door,
+            bounds,
+        }
+    }
+
+    /// Creates a `Host` from the `.env` file on a fresh database, dropping and recreating the
+    /// database named in the [`Posture`].  Intended for tests; use [`Host::new`] to keep the
+    /// existing data.
     pub async fn from_env() -> Polite<Self> {
         let posture = Posture::from_env()?;
         info!("Connection: {}", &posture.introduction().expose_secret());
@@ -22,27 +56,189 @@ impl Host {
         posture.create().await?;
         posture.migrate().await?;
         let recall = Recall::from(posture.clone());
-        Ok(Self { recall, posture })
+        let door = Door::default();
+        let bounds = Bounds::default();
+        Ok(Self {
+            recall,
+            posture,
+            door,
+            bounds,
+        })
     }
 
+    /// The `bearing` method returns the routes of the API, described by the [`chart::Chart`] and
+    /// served as JSON at `/openapi.json`.  With the `redoc` feature, a Redoc page rendering the
+    /// description is served at `/docs`.  Each request is tagged with an id and traced; see
+    /// [`Usher::escort`].  Metrics are counted by [`Tally::count`] and served at `/metrics`.
+    /// Requests are rate limited by the `door`, see [`Door::admit`], and limited in size and time
+    /// by the `bounds`, see [`Bounds::bound`].  Logins at `/guests/login` are guarded by the
+    /// lockouts of the `door`; see [`door::login`].
     pub fn bearing(&self) -> Router {
-        Router::new()
-            .route("/health", get(Counsel::check))
-            .route("/book", get(Counsel::book))
-            .route("/guests", get(Counsel::lookup_all).post(Counsel::check_in))
+        Tally::handle();
+        let router = Router::new()
+            .route("/health", get(route::health))
+            .route("/health/live", get(route::live))
+            .route("/health/ready", get(route::ready))
+            .route("/book", get(route::book))
+            .route("/openapi.json", get(chart::openapi))
+            .route("/metrics", get(tally::scrape))
+            .route("/guests", get(route::lookup_all).post(route::check_in))
             .route(
-                "/guests/:id",
-                get(Counsel::lookup)
-                    .put(Counsel::update)
-                    .delete(Counsel::check_out),
+                "/guests/batch",
+                post(route::check_in_batch)
+                    .put(route::update_batch)
+                    .delete(route::check_out_batch),
             )
-            .route("/improv/name", get(Counsel::guest_name))
-            .route("/improv/name/num", get(Counsel::guest_name_numbered))
+            .route("/guests/by-name/:name", get(route::lookup_name))
             .route(
-                "/improv/pass",
-                get(Counsel::guest_pass).post(Counsel::pass_adv),
+                "/guests/:id",
+                get(route::lookup)
+                    .put(route::update)
+                    .delete(route::check_out),
             )
-            // .route("/improv/pass/:length/:numbers/:lowercase/:uppercase/:symbols/:spaces/:exclude/:strict", get(Counsel::pass_adv))
-            .with_state(self.recall.book.clone())
+            .route("/admin/guests/deleted", get(route::departed))
+            .route("/admin/guests/sweep", post(route::sweep))
+            .route("/admin/guests/:id", delete(route::purge))
+            .route("/admin/guests/:id/restore", post(route::restore))
+            .route("/admin/audits", get(route::audits))
+            .route("/improv/name", get(route::guest_name))
+            .route("/improv/name/num", get(route::guest_name_numbered))
+            .route("/improv/pass", get(route::guest_pass).post(route::pass_adv))
+            .route("/improv/names", get(route::names))
+            .route("/improv/passes", post(route::passes))
+            .route("/improv/guests", get(route::guests))
+            .route("/improv/passphrase", post(route::passphrase))
+            .route("/improv/score", post(route::score))
+            // .route("/improv/pass/:length/:numbers/:lowercase/:uppercase/:symbols/:spaces/:exclude/:strict", get(route::pass_adv))
+            .with_state(self.recall.clone())
+            .merge(
+                Router::new()
+                    .route("/guests/login", post(door::login))
+                    .with_state(self.clone()),
+            );
+        #[cfg(feature = "redoc")]
+        let router = router.route("/docs", get(chart::docs));
+        let router = self
+            .bounds
+            .bound(router)
+            .layer(middleware::from_fn_with_state(
+                self.door.clone(),
+                Door::admit,
+            ))
+            .layer(middleware::from_fn(Tally::count));
+        Usher::escort(router)
+    }
+
+    /// The `guarded` method returns a copy of the `Host` sharing the same database, behind a new
+    /// [`Door`] enforcing `doorman`.  Commits a [`polite::FauxPas::Invalid`] if the settings fail
+    /// [`Doorman::validate`].
+    pub fn guarded(&self, doorman: &Doorman) -> Polite<Self> {
+        doorman.validate()?;
+        Ok(Self {
+            door: Door::new(doorman.clone()),
+            ..self.clone()
+        })
+    }
+
+    /// The `bounded` method returns a copy of the `Host` sharing the same database and door,
+    /// limiting requests to `bounds`.  Commits a [`polite::FauxPas::Invalid`] if the settings fail
+    /// [`Bounds::validate`].
+    pub fn bounded(&self, bounds: &Bounds) -> Polite<Self> {
+        bounds.validate()?;
+        Ok(Self {
+            bounds: *bounds,
+            ..self.clone()
+        })
+    }
+
+    /// The `welcoming` method returns the routes of [`Host::bearing`] behind the CORS layer
+    /// described by `welcome`.  Commits a [`polite::FauxPas::Invalid`] if the settings fail
+    /// [`Welcome::validate`].
+    pub fn welcoming(&self, welcome: &Welcome) -> Polite<Router> {
+        Ok(self.bearing().layer(welcome.layer()?))
+    }
+
+    /// The `serve` method binds to the address of `venue` and serves the routes of
+    /// [`Host::welcoming`] until the process receives SIGINT or SIGTERM.  See
+    /// [`Host::serve_until`].
+    pub async fn serve(&self, venue: &Venue) -> Polite<()> {
+        let listener = TcpListener::bind(&venue.addr).await?;
+        self.serve_until(listener, venue, Host::signal()).await
+    }
+
+    /// The `serve_until` method serves the routes of [`Host::welcoming`] on `listener`, using the
+    /// CORS, rate limit and request limit settings of `venue`, until `shutdown` completes.  Open
+    /// connections then have the `grace` period of `venue` to finish before they are closed, and
+    /// the connection pool of the database closes last.  The address of `venue` is ignored in
+    /// favor of `listener`.  The address of each client is recorded in the audit trail; see
+    /// [`counsel::Attended`].
+    pub async fn serve_until<F>(
+        &self,
+        listener: TcpListener,
+        venue: &Venue,
+        shutdown: F,
+    ) -> Polite<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let router = self
+            .guarded(&venue.doorman)?
+            .bounded(&venue.bounds)?
+            .welcoming(&venue.welcome)?;
+        let grace = venue.grace;
+        info!("Listening on {}.", listener.local_addr()?);
+        let (draining, drain) = oneshot::channel();
+        let router = router.into_make_service_with_connect_info::<SocketAddr>();
+        let server = axum::serve(listener, router).with_graceful_shutdown(async move {
+            shutdown.await;
+            info!("Shutting down, draining open connections.");
+            draining.send(()).ok();
+        });
+        let mut server = tokio::spawn(server.into_future());
+        let served = tokio::select! {
+            served = &mut server => Some(served),
+            _ = drain => match tokio::time::timeout(grace, &mut server).await {
+                Ok(served) => Some(served),
+                Err(_) => {
+                    warn!("Grace period elapsed, closing remaining connections.");
+                    server.abort();
+                    None
+                }
+            },
+        };
+        self.recall.book.close().await;
+        info!("Server stopped.");
+        match served {
+            Some(served) => Ok(served.map_err(std::io::Error::other)??),
+            None => Ok(()),
+        }
+    }
+
+    /// Completes when the process receives SIGINT, or SIGTERM on Unix.
+    pub async fn signal() {
+        let interrupt = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                warn!("Failed to listen for SIGINT: {}", e);
+                std::future::pending::<()>().await;
+            }
+        };
+        #[cfg(unix)]
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut signal) => {
+                    signal.recv().await;
+                }
+                Err(e) => {
+                    warn!("Failed to listen for SIGTERM: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+        tokio::select! {
+            _ = interrupt => {},
+            _ = terminate => {},
+        }
     }
 }
@@ -1,3 +1,21 @@
+pub mod bounds;
+pub mod chart;
+pub mod courier;
+pub mod door;
+pub mod doorman;
 pub mod host;
+pub mod tally;
+pub mod usher;
+pub mod venue;
+pub mod welcome;
 
+pub use crate::bounds::Bounds;
+pub use crate::chart::Chart;
+pub use crate::courier::Courier;
+pub use crate::door::{Credentials, Door, Quota};
+pub use crate::doorman::{Allowance, Doorman, Group, Lockout, Rate};
 pub use crate::host::Host;
+pub use crate::tally::Tally;
+pub use crate::usher::Usher;
+pub use crate::venue::Venue;
+pub use crate::welcome::Welcome;
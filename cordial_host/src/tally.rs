@@ -0,0 +1,128 @@
+//! The `tally` module contains the [`Tally`] struct, which collects metrics from the
+//! [`crate::Host`] and serves them at `/metrics` in the Prometheus text format.
+//!
+//! The metric names are stable:
+//!
+//! | Name | Type | Labels |
+//! | ---- | ---- | ------ |
+//! | `cordial_http_requests_total` | counter | `method`, `route`, `status` |
+//! | `cordial_http_request_duration_seconds` | histogram | `method`, `route`, `status` |
+//! | `cordial_recall_query_duration_seconds` | histogram | `operation`, `outcome` |
+//! | `cordial_pool_connections` | gauge | `state` |
+//! | `cordial_pool_max_connections` | gauge | |
+//! | `cordial_improv_generated_total` | counter | `kind` |
+use crate::usher::Usher;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use cordial_improv::Improv;
+use cordial_recall::{vitals, Recall};
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::warn;
+
+/// The `Tally` struct holds the metrics recorder shared by every [`crate::Host`] in the process.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Tally;
+
+impl Tally {
+    /// The name of the counter of answered requests.  Labelled with the `method`, the `route`
+    /// template, or `unmatched`, and the `status` code.
+    pub const REQUESTS: &'static str = "cordial_http_requests_total";
+    /// The name of the histogram timing each request in seconds, from arrival until the response
+    /// head is ready.  Labelled like [`Tally::REQUESTS`].
+    pub const REQUEST_SECONDS: &'static str = "cordial_http_request_duration_seconds";
+    /// The name of the gauge of open database connections, labelled with the `state`, either
+    /// `idle` or `busy`.  Measured when the metrics are scraped.
+    pub const POOL_CONNECTIONS: &'static str = "cordial_pool_connections";
+    /// The name of the gauge of the largest number of connections the pool will open.
+    pub const POOL_MAX: &'static str = "cordial_pool_max_connections";
+    /// The upper bounds of the buckets of every histogram measured in seconds.
+    pub const BUCKETS: [f64; 13] = [
+        0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ];
+    /// The content type of the Prometheus text format.
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4; charset=utf-8";
+
+    /// The `handle` method returns the handle to the process-wide metrics recorder, installing
+    /// the recorder on first call.  Metrics recorded before then are dropped.
+    pub fn handle() -> &'static PrometheusHandle {
+        static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+        HANDLE.get_or_init(|| {
+            let recorder = PrometheusBuilder::new()
+                .set_buckets_for_metric(Matcher::Suffix("seconds".to_string()), &Self::BUCKETS)
+                .map(PrometheusBuilder::build_recorder)
+                .unwrap_or_else(|e| {
+                    warn!("Recording histograms as summaries: {}", e);
+                    PrometheusBuilder::new().build_recorder()
+                });
+            let handle = recorder.handle();
+            if let Err(e) = metrics::set_global_recorder(recorder) {
+                warn!("Metrics recorder not installed: {}", e);
+            }
+            Self::describe();
+            handle
+        })
+    }
+
+    /// The `count` middleware records each request under [`Tally::REQUESTS`] and
+    /// [`Tally::REQUEST_SECONDS`].
+    pub async fn count(request: Request, next: Next) -> Response {
+        let started = Instant::now();
+        let method = request.method().to_string();
+        let route = Usher::route(&request).to_string();
+        let response = next.run(request).await;
+        let labels = [
+            ("method", method),
+            ("route", route),
+            ("status", response.status().as_u16().to_string()),
+        ];
+        metrics::counter!(Self::REQUESTS, &labels).increment(1);
+        metrics::histogram!(Self::REQUEST_SECONDS, &labels).record(started.elapsed().as_secs_f64());
+        response
+    }
+
+    fn describe() {
+        describe_counter!(Self::REQUESTS, "Requests answered by the host.");
+        describe_histogram!(
+            Self::REQUEST_SECONDS,
+            Unit::Seconds,
+            "Time taken to answer each request."
+        );
+        describe_histogram!(
+            Recall::QUERY_SECONDS,
+            Unit::Seconds,
+            "Time taken by each guest book operation."
+        );
+        describe_gauge!(Self::POOL_CONNECTIONS, "Open database connections.");
+        describe_gauge!(Self::POOL_MAX, "Largest number of database connections.");
+        describe_counter!(Improv::GENERATED, "Names, passes and guests improvised.");
+    }
+}
+
+/// The `scrape` method measures the connection pool behind `recall` and returns every metric in
+/// the Prometheus text format.  See [`Tally`].
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    summary = "Reports request, database and improv metrics in the Prometheus text format.",
+    responses(
+        (status = 200, description = "The current metrics.", body = String, content_type = "text/plain; version=0.0.4"),
+    )
+)]
+pub async fn scrape(State(recall): State<Recall>) -> impl IntoResponse {
+    let handle = Tally::handle();
+    let pool = vitals::pool(&recall.book);
+    metrics::gauge!(Tally::POOL_CONNECTIONS, "state" => "idle").set(pool.idle as f64);
+    metrics::gauge!(Tally::POOL_CONNECTIONS, "state" => "busy").set(pool.busy);
+    metrics::gauge!(Tally::POOL_MAX).set(pool.max);
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, Tally::CONTENT_TYPE)],
+        handle.render(),
+    )
+}
@@ -0,0 +1,214 @@
+//! The `usher` module contains the [`Usher`] struct, which tags each request to the
+//! [`crate::Host`] with an id and follows it with a tracing span, so the lines logged while
+//! handling it can be told apart from those of other requests.
+use axum::body::Body;
+use axum::extract::MatchedPath;
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::IntoResponse;
+use axum::{Json, Router};
+use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+use opentelemetry::trace::{TraceContextExt, TracerProvider};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use polite::{Polite, Problem};
+use std::time::Duration;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::TraceLayer;
+use tracing::{info, info_span, warn, Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// The `Usher` struct holds the middleware assigning request ids and tracing spans.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Usher;
+
+impl Usher {
+    /// The header carrying the request id.  An id sent by the client is kept, otherwise a new
+    /// UUID is assigned, and either way the id is returned in the response.
+    pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+    /// The most bytes of a plain text refusal read into a [`Problem`].
+    const REJECTION_MAX: usize = 64 * 1024;
+
+    /// The `escort` method wraps every route of `router` so each request gets an id and a span
+    /// named `request`.  The span records the `method`, the `route` template, the `request_id`,
+    /// and once answered, the `status` and `latency_ms`.  When traces are exported, a W3C
+    /// `traceparent` header on the request makes the span a child of the caller's, and the
+    /// response carries the `traceparent` of the span.  An error answered with a [`Problem`]
+    /// names the request in its `request_id`.
+    pub fn escort(router: Router) -> Router {
+        router
+            .layer(middleware::from_fn(Self::sign))
+            .layer(middleware::map_response(Self::stamp))
+            .layer(PropagateRequestIdLayer::new(Self::REQUEST_ID))
+            .layer(
+                TraceLayer::new_for_http()
+                    .make_span_with(Self::span)
+                    .on_request(())
+                    .on_response(Self::answered),
+            )
+            .layer(SetRequestIdLayer::new(Self::REQUEST_ID, MakeRequestUuid))
+    }
+
+    /// The `subscribe` method installs the subscriber built by [`Usher::subscriber`] as the
+    /// global default.  Commits a [`polite::FauxPas::TraceInit`] if a subscriber is already
+    /// installed.
+    pub fn subscribe(default: &str, provider: Option<&SdkTracerProvider>) -> Polite<()> {
+        Self::subscriber(default, provider).try_init()?;
+        Ok(())
+    }
+
+    /// The `subscriber` method builds a tracing subscriber writing to standard error, filtered by
+    /// `RUST_LOG`, or by `default` if `RUST_LOG` is not set.  Spans are also sent to `provider`
+    /// if present, such as one built by [`crate::Courier::dispatch`].
+    pub fn subscriber(
+        default: &str,
+        provider: Option<&SdkTracerProvider>,
+    ) -> impl Subscriber + Send + Sync + 'static {
+        let export = provider
+            .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer("cordial")));
+        tracing_subscriber::registry()
+            .with(export)
+            .with(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| default.into()),
+            )
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+    }
+
+    /// The `route` method returns the route template matched by `request`, such as
+    /// `/guests/:id`, or `unmatched` if no route matched.
+    pub fn route<B>(request: &Request<B>) -> &str {
+        request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(MatchedPath::as_str)
+            .unwrap_or("unmatched")
+    }
+
+    fn span<B>(request: &Request<B>) -> Span {
+        let route = Self::route(request);
+        let id = request
+            .headers()
+            .get(Self::REQUEST_ID)
+            .and_then(|id| id.to_str().ok())
+            .unwrap_or_default();
+        let span = info_span!(
+            "request",
+            otel.name = format!("{} {}", request.method(), route),
+            otel.kind = "server",
+            method = %request.method(),
+            route,
+            request_id = id,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        );
+        let parent = TraceContextPropagator::new().extract(&Headers(request.headers()));
+        if parent.span().span_context().is_valid() {
+            if let Err(e) = span.set_parent(parent) {
+                warn!("Trace context not continued: {}", e);
+            }
+        }
+        span
+    }
+
+    /// Adds the `traceparent` of the current span to `response`, if the span is exported.
+    async fn stamp<B>(mut response: Response<B>) -> Response<B> {
+        let context = Span::current().context();
+        if context.span().span_context().is_valid() {
+            TraceContextPropagator::new()
+                .inject_context(&context, &mut Stamp(response.headers_mut()));
+        }
+        response
+    }
+
+    /// Fills in the `request_id` of a [`Problem`] answering `request` from its request id header.
+    /// The extractors of axum refuse a request with a plain text `400` or `422`; the text becomes
+    /// the `detail` of a [`Problem`] so that every refusal has the same shape.
+    async fn sign(request: Request<Body>, next: Next) -> Response<Body> {
+        let id = request
+            .headers()
+            .get(Self::REQUEST_ID)
+            .and_then(|id| id.to_str().ok())
+            .map(str::to_owned);
+        let mut response = next.run(request).await;
+        let problem = match response.extensions_mut().remove::<Problem>() {
+            Some(problem) => problem,
+            None if Self::rejected(&response) => {
+                let (parts, body) = response.into_parts();
+                let detail = match axum::body::to_bytes(body, Self::REJECTION_MAX).await {
+                    Ok(detail) => String::from_utf8_lossy(&detail).into_owned(),
+                    Err(e) => e.to_string(),
+                };
+                let status = parts.status;
+                response = Response::from_parts(parts, Body::empty());
+                Problem::new(
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or_default(),
+                    &detail,
+                )
+            }
+            None => return response,
+        };
+        let problem = Problem {
+            request_id: id,
+            ..problem
+        };
+        let headers = response.headers_mut();
+        headers.remove(header::CONTENT_LENGTH);
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(Problem::CONTENT_TYPE),
+        );
+        *response.body_mut() = Json(&problem).into_response().into_body();
+        response.extensions_mut().insert(problem);
+        response
+    }
+
+    /// Whether `response` is a plain text refusal from an extractor.
+    fn rejected<B>(response: &Response<B>) -> bool {
+        matches!(
+            response.status(),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY
+        ) && response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .is_some_and(|media| media.as_bytes().starts_with(b"text/plain"))
+    }
+
+    fn answered<B>(response: &Response<B>, latency: Duration, span: &Span) {
+        span.record("status", response.status().as_u16());
+        span.record("latency_ms", latency.as_millis() as u64);
+        info!("Answered {}.", response.status());
+    }
+}
+
+/// Reads trace context from the headers of a request.
+struct Headers<'a>(&'a HeaderMap);
+
+impl Extractor for Headers<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+/// Writes trace context to the headers of a response.
+struct Stamp<'a>(&'a mut HeaderMap);
+
+impl Injector for Stamp<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if value.is_empty() {
+            return;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
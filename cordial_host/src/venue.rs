@@ -0,0 +1,80 @@
+//! The `venue` module contains the [`Venue`] struct, holding the settings of the server run by
+//! [`crate::Host::serve`].
+use crate::bounds::Bounds;
+use crate::courier::Courier;
+use crate::doorman::Doorman;
+use crate::welcome::Welcome;
+use polite::Polite;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The `Venue` struct holds the address the server binds to, how long it waits for open
+/// connections to finish after a shutdown signal, which browser origins it welcomes, how fast
+/// callers may send requests, how large and long each request may be, and where its traces are
+/// sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Venue {
+    /// The `addr` field holds the socket address to bind, such as `127.0.0.1:8000`.
+    pub addr: String,
+    /// The `grace` field holds the time allowed for draining connections on shutdown, after which
+    /// the remaining connections are closed.
+    pub grace: Duration,
+    /// The `welcome` field holds the CORS settings applied to every route.
+    pub welcome: Welcome,
+    /// The `doorman` field holds the rate limits and login lockouts applied to every route.
+    pub doorman: Doorman,
+    /// The `bounds` field holds the body size and time limits applied to every request.
+    pub bounds: Bounds,
+    /// The `courier` field holds the trace export settings, read by the binary when installing
+    /// the tracing subscriber.
+    pub courier: Courier,
+}
+
+impl Venue {
+    /// The default address.
+    pub const ADDR: &'static str = "127.0.0.1:8000";
+    /// The default grace period of thirty seconds.
+    pub const GRACE: Duration = Duration::from_secs(30);
+
+    /// Creates a `Venue` from the default method.  Modify the fields directly after construction
+    /// to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `from_env` method creates a `Venue` from the optional variables `HOST_ADDR` and
+    /// `HOST_GRACE_SECS`, using the defaults for those not set, its `welcome` from
+    /// [`Welcome::from_env`], its `doorman` from [`Doorman::from_env`], its `bounds` from
+    /// [`Bounds::from_env`] and its `courier` from [`Courier::from_env`].  Commits a
+    /// [`polite::FauxPas`] if `HOST_GRACE_SECS` is not a whole number or the CORS, rate limit,
+    /// request limit or trace export settings cannot be parsed.
+    pub fn from_env() -> Polite<Self> {
+        dotenvy::dotenv().ok();
+        let mut venue = Self::default();
+        if let Ok(addr) = std::env::var("HOST_ADDR") {
+            venue.addr = addr;
+        }
+        if let Ok(grace) = std::env::var("HOST_GRACE_SECS") {
+            venue.grace = Duration::from_secs(grace.parse()?);
+        }
+        venue.welcome = Welcome::from_env()?;
+        venue.doorman = Doorman::from_env()?;
+        venue.bounds = Bounds::from_env()?;
+        venue.courier = Courier::from_env()?;
+        Ok(venue)
+    }
+}
+
+impl Default for Venue {
+    fn default() -> Self {
+        Self {
+            addr: Self::ADDR.to_string(),
+            grace: Self::GRACE,
+            welcome: Welcome::default(),
+            doorman: Doorman::default(),
+            bounds: Bounds::default(),
+            courier: Courier::default(),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! The `welcome` module contains the [`Welcome`] struct, holding the cross-origin resource sharing
+//! (CORS) settings applied to every route of the [`crate::Host`].
+use crate::usher::Usher;
+use axum::http::header::HeaderName;
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, Method};
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
+
+/// The `Welcome` struct holds which browser origins may call the server, and what they may send.
+/// Each entry of `origins` is one of:
+///
+/// * An exact origin, such as `https://app.example.com` or `http://127.0.0.1:8080`.
+/// * A wildcard subdomain, such as `https://*.example.com`, matching any subdomain of
+///   `example.com` with the same scheme and port, but not `example.com` itself.
+/// * A lone `*`, allowing any origin.
+///
+/// The `methods` and `headers` fields also accept a lone `*` for any.  Wildcards cannot be
+/// combined with `credentials`, as browsers reject them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Welcome {
+    /// The `origins` field holds the origins allowed to make requests.
+    pub origins: Vec<String>,
+    /// The `methods` field holds the HTTP methods allowed in requests.
+    pub methods: Vec<String>,
+    /// The `headers` field holds the request headers allowed in requests.
+    pub headers: Vec<String>,
+    /// The `credentials` field allows requests to include cookies and authorization headers.
+    pub credentials: bool,
+    /// The `max_age` field holds how long browsers may cache a preflight response, if set.
+    pub max_age: Option<Duration>,
+}
+
+impl Welcome {
+    /// The origins allowed by default, the local server and client.
+    pub const ORIGINS: [&'static str; 2] = ["http://127.0.0.1:8000", "http://127.0.0.1:8080"];
+    /// The methods allowed by default.
+    pub const METHODS: [&'static str; 5] = ["GET", "POST", "PUT", "DELETE", "OPTIONS"];
+    /// The request headers allowed by default.
+    pub const HEADERS: [&'static str; 3] = ["accept", "content-type", "x-actor"];
+    /// The default preflight cache duration of one hour.
+    pub const MAX_AGE: Duration = Duration::from_secs(3600);
+    /// The entry allowing any origin, method or header.
+    pub const ANY: &'static str = "*";
+
+    /// Creates a `Welcome` from the default method.  Modify the fields directly after
+    /// construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `from_env` method creates a `Welcome` from the optional variables `CORS_ORIGINS`,
+    /// `CORS_METHODS` and `CORS_HEADERS`, each a comma-separated list, along with
+    /// `CORS_CREDENTIALS` (`true` or `false`) and `CORS_MAX_AGE_SECS`, using the defaults for
+    /// those not set.  A `CORS_MAX_AGE_SECS` of zero leaves the preflight uncached.  Commits a
+    /// [`FauxPas`] if a value cannot be parsed.
+    pub fn from_env() -> Polite<Self> {
+        dotenvy::dotenv().ok();
+        let mut welcome = Self::default();
+        if let Ok(origins) = std::env::var("CORS_ORIGINS") {
+            welcome.origins = Self::list(&origins);
+        }
+        if let Ok(methods) = std::env::var("CORS_METHODS") {
+            welcome.methods = Self::list(&methods);
+        }
+        if let Ok(headers) = std::env::var("CORS_HEADERS") {
+            welcome.headers = Self::list(&headers);
+        }
+        if let Ok(credentials) = std::env::var("CORS_CREDENTIALS") {
+            welcome.credentials = credentials.trim().parse().map_err(|_| {
+                Self::flaw(
+                    "credentials",
+                    "format",
+                    &format!("Must be true or false, found {}.", credentials),
+                )
+            })?;
+        }
+        if let Ok(max_age) = std::env::var("CORS_MAX_AGE_SECS") {
+            welcome.max_age = match max_age.trim().parse()? {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            };
+        }
+        Ok(welcome)
+    }
+
+    /// The `validate` method checks that every origin, method and header is well formed, and that
+    /// wildcards are not combined with `credentials`.  Commits a [`FauxPas::Invalid`] listing
+    /// each [`Flaw`] found.
+    pub fn validate(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        for origin in &self.origins {
+            if let Err(flaw) = Origin::parse(origin) {
+                flaws.push(flaw);
+            }
+        }
+        for method in self.methods.iter().filter(|m| !Self::any(m)) {
+            if Method::from_bytes(method.as_bytes()).is_err() {
+                flaws.push(Flaw::new(
+                    "methods",
+                    "format",
+                    &format!("Invalid method {}.", method),
+                ));
+            }
+        }
+        for header in self.headers.iter().filter(|h| !Self::any(h)) {
+            if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                flaws.push(Flaw::new(
+                    "headers",
+                    "format",
+                    &format!("Invalid header name {}.", header),
+                ));
+            }
+        }
+        if self.credentials {
+            for (field, list) in [
+                ("origins", &self.origins),
+                ("methods", &self.methods),
+                ("headers", &self.headers),
+            ] {
+                if list.iter().any(|entry| Self::any(entry)) {
+                    flaws.push(Flaw::new(
+                        field,
+                        "credentials",
+                        "Cannot allow any with credentials.",
+                    ));
+                }
+            }
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+
+    /// The `layer` method builds the [`CorsLayer`] described by the `Welcome`, which answers
+    /// preflight requests and adds the CORS headers to every response.  Commits a
+    /// [`FauxPas::Invalid`] if the settings fail [`Welcome::validate`].
+    pub fn layer(&self) -> Polite<CorsLayer> {
+        self.validate()?;
+        let origins = match self.origins.iter().any(|o| Self::any(o)) {
+            true => AllowOrigin::from(Any),
+            false => {
+                let origins = self
+                    .origins
+                    .iter()
+                    .filter_map(|origin| Origin::parse(origin).ok())
+                    .collect::<Vec<_>>();
+                AllowOrigin::predicate(move |origin: &HeaderValue, _: &Parts| {
+                    origin
+                        .to_str()
+                        .is_ok_and(|origin| origins.iter().any(|o| o.matches(origin)))
+                })
+            }
+        };
+        let methods = match self.methods.iter().any(|m| Self::any(m)) {
+            true => AllowMethods::from(Any),
+            false => AllowMethods::list(
+                self.methods
+                    .iter()
+                    .filter_map(|method| Method::from_bytes(method.as_bytes()).ok()),
+            ),
+        };
+        let headers = match self.headers.iter().any(|h| Self::any(h)) {
+            true => AllowHeaders::from(Any),
+            false => AllowHeaders::list(
+                self.headers
+                    .iter()
+                    .filter_map(|header| HeaderName::from_bytes(header.as_bytes()).ok()),
+            ),
+        };
+        let mut layer = CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .expose_headers([Usher::REQUEST_ID])
+            .allow_credentials(self.credentials);
+        if let Some(max_age) = self.max_age {
+            layer = layer.max_age(max_age);
+        }
+        Ok(layer)
+    }
+
+    fn any(entry: &str) -> bool {
+        entry.trim() == Self::ANY
+    }
+
+    fn list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn flaw(field: &str, rule: &str, message: &str) -> FauxPas {
+        FauxPas::Invalid(vec![Flaw::new(field, rule, message)])
+    }
+}
+
+impl Default for Welcome {
+    fn default() -> Self {
+        Self {
+            origins: Self::ORIGINS.iter().map(|o| o.to_string()).collect(),
+            methods: Self::METHODS.iter().map(|m| m.to_string()).collect(),
+            headers: Self::HEADERS.iter().map(|h| h.to_string()).collect(),
+            credentials: false,
+            max_age: Some(Self::MAX_AGE),
+        }
+    }
+}
+
+/// An allowed origin, parsed from an entry of [`Welcome::origins`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Origin {
+    /// Matches any origin.
+    Any,
+    /// Matches the origin exactly.
+    Exact(String),
+    /// Matches subdomains, holding the scheme with separator and the parent domain with port,
+    /// such as `https://` and `.example.com`.
+    Subdomain(String, String),
+}
+
+impl Origin {
+    fn parse(entry: &str) -> Result<Self, Flaw> {
+        let entry = entry.trim();
+        if entry == Welcome::ANY {
+            return Ok(Self::Any);
+        }
+        let flaw = |message: &str| {
+            Flaw::new(
+                "origins",
+                "format",
+                &format!("Invalid origin {}: {}", entry, message),
+            )
+        };
+        let (scheme, host) = entry
+            .split_once("://")
+            .ok_or_else(|| flaw("expected a scheme such as https://."))?;
+        if scheme.is_empty()
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+')
+        {
+            return Err(flaw("expected a scheme such as https://."));
+        }
+        if host.is_empty() || host.contains(['/', '?', '#', '@']) {
+            return Err(flaw("expected a host without a path."));
+        }
+        let scheme = format!("{}://", scheme.to_ascii_lowercase());
+        let host = host.to_ascii_lowercase();
+        match host.strip_prefix('*') {
+            Some(parent)
+                if parent.starts_with('.') && parent.len() > 1 && !parent.contains('*') =>
+            {
+                Ok(Self::Subdomain(scheme, parent.to_string()))
+            }
+            Some(_) => Err(flaw("wildcards must take the form *.example.com.")),
+            None if host.contains('*') => Err(flaw("wildcards must take the form *.example.com.")),
+            None => Ok(Self::Exact(format!("{}{}", scheme, host))),
+        }
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        match self {
+            Self::Any => true,
+            Self::Exact(exact) => origin == *exact,
+            Self::Subdomain(scheme, parent) => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|host| host.strip_suffix(parent.as_str()))
+                .is_some_and(|sub| {
+                    !sub.is_empty()
+                        && sub
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                        && !sub.starts_with('.')
+                        && !sub.ends_with('.')
+                }),
+        }
+    }
+}
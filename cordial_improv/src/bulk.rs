@@ -0,0 +1,61 @@
+//! The `bulk` module holds the [`Bulk`] struct, the query parameters of the routes that improvise
+//! many names, passwords or guests at once.
+use crate::improv::Improv;
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+/// The `Bulk` struct holds the number of items to improvise, and an optional seed for
+/// reproducible results.  Deserializes from the query string of the `/improv/names`,
+/// `/improv/passes` and `/improv/guests` routes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+pub struct Bulk {
+    /// The number of items requested, from 1 to [`Bulk::MAX`].
+    pub count: usize,
+    /// Seeds the generator when present, so that the same request returns the same items.  See
+    /// [`Improv::seeded`].
+    pub seed: Option<u64>,
+}
+
+impl Bulk {
+    /// The largest number of items in a single request.
+    pub const MAX: usize = 1000;
+
+    /// Creates a new `Bulk` struct from the default method.  Modify the fields directly after
+    /// construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `validate` method checks that `count` is between 1 and [`Bulk::MAX`].  Commits a
+    /// [`FauxPas::Invalid`] otherwise.
+    pub fn validate(&self) -> Polite<()> {
+        match (1..=Self::MAX).contains(&self.count) {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(vec![Flaw::new(
+                "count",
+                "range",
+                &format!("Must be between 1 and {}.", Self::MAX),
+            )])),
+        }
+    }
+
+    /// The `improv` method returns a numbered [`Improv`], seeded from the `seed` field if present.
+    pub fn improv(&self) -> Improv {
+        match self.seed {
+            Some(seed) => Improv::seeded(seed),
+            None => Improv::new(true),
+        }
+    }
+}
+
+impl Default for Bulk {
+    fn default() -> Self {
+        Self {
+            count: 10,
+            seed: None,
+        }
+    }
+}
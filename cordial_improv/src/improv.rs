@@ -1,52 +1,103 @@
-//! The `improv` crate provides methods for generating random names and passwords, using the
-//! eponymous [`names`] and [`passwords`] crates.
+//! The `improv` crate provides methods for generating random names and passwords, drawing on the
+//! wordlists of the [`names`] crate and the options of the [`passwords`] crate, and passphrases
+//! drawn from the EFF large wordlist.
+use crate::lexicon::Lexicon;
+use crate::pattern::Pattern;
 use cordial_guest::Guest;
-use names::{Generator, Name};
 use passwords::PasswordGenerator;
-use polite::{FauxPas, Polite};
+use polite::{FauxPas, Flaw, Polite};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use utoipa::ToSchema;
 
-/// The `Improv` struct produces randomized names and passwords.
-pub struct Improv<'a> {
-    /// The `name` field contains a [`Generator`] from the [`names`] crate.
-    pub name: Generator<'a>,
-    /// The `pass` field contains a [`PasswordGenerator`] from the [`passwords`] crate.
+/// The `Improv` struct produces randomized names and passwords.  Every value comes from a single
+/// random number generator, so an `Improv` created with [`Improv::seeded`] produces the same
+/// names, passwords and guest ids on every run.
+#[derive(Debug, Clone)]
+pub struct Improv {
+    /// The `numbered` field appends a four-digit number to each name when `true`, following the
+    /// [`names::Name::Numbered`] pattern.
+    pub numbered: bool,
+    /// The `pass` field contains a [`PasswordGenerator`] from the [`passwords`] crate, holding the
+    /// options used by [`Improv::pass`].
     pub pass: PasswordGenerator,
+    rng: ChaCha12Rng,
 }
 
-impl<'a> Improv<'a> {
-    /// The `new` method creates an `Improv` struct, using the naming pattern [`Name::Numbered`] if
-    /// `numbered` is `true`, and [`Name::Plain`] if `false`.
+impl Improv {
+    /// The number of times [`Improv::pattern`] draws a name before giving up on finding one free
+    /// of blocked words.
+    pub const ATTEMPTS: usize = 16;
+    /// The name of the counter of values produced by an `Improv`, labelled with the `kind` of
+    /// value: `name`, `pass`, `passphrase` or `guest`.  Each improvised guest also counts its
+    /// name and pass.
+    pub const GENERATED: &'static str = "cordial_improv_generated_total";
+
+    /// The `new` method creates an `Improv` struct, using the naming pattern
+    /// [`names::Name::Numbered`] if `numbered` is `true`, and [`names::Name::Plain`] if `false`.
+    /// Seeds the generator from the operating system.
     pub fn new(numbered: bool) -> Self {
-        let name = Improv::gen(numbered);
-        let pass = PasswordGenerator::new();
-        Self { name, pass }
+        Self::from_pass(PasswordGenerator::new(), numbered)
     }
 
-    fn gen(numbered: bool) -> Generator<'a> {
-        match numbered {
-            true => Generator::with_naming(Name::Numbered),
-            false => Generator::with_naming(Name::Plain),
+    /// The `seeded` method creates a numbered `Improv` struct whose generator starts from `seed`.
+    /// The same `seed` produces the same sequence of names, passwords and guests.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            numbered: true,
+            pass: PasswordGenerator::new(),
+            rng: ChaCha12Rng::seed_from_u64(seed),
         }
     }
 
     pub fn from_pass(pass: PasswordGenerator, numbered: bool) -> Self {
-        let name = Improv::gen(numbered);
-        Self { name, pass }
+        Self {
+            numbered,
+            pass,
+            rng: ChaCha12Rng::from_entropy(),
+        }
     }
 
-    /// The `name` method calls [`Generator::next`] to produce an [`Option`] where the [`Some`] variant contains a new name.  Commits a [`FauxPas`]
-    /// if the [`Option`] is [`None`].
+    /// The `name` method joins a random adjective and noun from the wordlists of the [`names`]
+    /// crate, adding four random digits if `numbered` is `true`.  See [`Pattern::naming`].
+    /// Commits a [`FauxPas`] if no clean name turns up.
     pub fn name(&mut self) -> Polite<String> {
-        let opt = self.name.next();
-        match opt {
-            Some(name) => Ok(name),
-            None => Err(FauxPas::Improv("Failed to generate name.".to_string())),
+        self.pattern(&Pattern::naming(self.numbered), Lexicon::standard())
+    }
+
+    /// The `pattern` method renders `pattern` with words from `lexicon`, drawing again if the
+    /// name contains a blocked word, up to [`Improv::ATTEMPTS`] times.  Commits a [`FauxPas`] if
+    /// the pattern names a missing wordlist or no clean name turns up.
+    pub fn pattern(&mut self, pattern: &Pattern, lexicon: &Lexicon) -> Polite<String> {
+        for _ in 0..Self::ATTEMPTS {
+            let name = pattern.render(lexicon, &mut self.rng)?;
+            if lexicon.clean(&name) {
+                Self::tally("name", 1);
+                return Ok(name);
+            }
         }
+        Err(FauxPas::Improv(format!(
+            "No clean name found for pattern {}.",
+            pattern
+        )))
+    }
+
+    /// The `patterns` method calls [`Improv::pattern`] `count` times.  Passes any [`FauxPas`] up.
+    pub fn patterns(
+        &mut self,
+        pattern: &Pattern,
+        lexicon: &Lexicon,
+        count: usize,
+    ) -> Polite<Vec<String>> {
+        (0..count).map(|_| self.pattern(pattern, lexicon)).collect()
     }
 
-    /// The `names` method calls [`Generator::next`] repeatedly to produce an [`Option`] where the [`Some`] variant contains a new name, entering successful names into a `String` vector.  Commits a [`FauxPas`]
-    /// if any [`Option`] contains [`None`].
+    /// The `names` method calls [`Improv::name`] repeatedly, entering successful names into a
+    /// `String` vector.  Commits a [`FauxPas`] if any name fails.
     pub fn names(&mut self, count: usize) -> Polite<Vec<String>> {
         let mut names = Vec::new();
         while names.len() < count {
@@ -55,29 +106,156 @@ impl<'a> Improv<'a> {
         Ok(names)
     }
 
-    /// The `pass` method calls [`PasswordGenerator::generate_one`] to produce a single password.
-    /// Not suitable for generating multiple passwords in a loop; use [`Improv::passes`] instead.  Commits
-    /// a [`FauxPas`] if the [`passwords`] library returns an error, bubbling the message up.
-    /// The `pass` field is public to expose the configuration
-    /// methods available in the [`passwords`] crate for the [`PasswordGenerator`].
-    pub fn pass(&self) -> Polite<String> {
-        let pass = self.pass.generate_one();
-        match pass {
-            Ok(value) => Ok(value),
-            Err(msg) => Err(FauxPas::Pass(msg.to_string())),
+    /// The `variants` method produces names resembling `wanted`, ranked from closest to furthest
+    /// by edit distance.  The variants append a number to `wanted`, or join it with a random
+    /// adjective or noun, with `count` variants of each kind.  The first entry is always `wanted`
+    /// itself.
+    pub fn variants(&mut self, wanted: &str, count: usize) -> Vec<String> {
+        let lexicon = Lexicon::standard();
+        let adjectives = lexicon.words("adjective").unwrap_or_default();
+        let nouns = lexicon.words("noun").unwrap_or_default();
+        let mut variants = vec![wanted.to_string()];
+        for n in 1..=count {
+            variants.push(format!("{}{}", wanted, n));
+            variants.push(format!("{}-{}", wanted, n));
         }
+        for _ in 0..count {
+            if let Some(adjective) = adjectives.choose(&mut self.rng) {
+                variants.push(format!("{}-{}", adjective, wanted));
+            }
+            if let Some(noun) = nouns.choose(&mut self.rng) {
+                variants.push(format!("{}-{}", wanted, noun));
+            }
+        }
+        let mut seen = HashSet::new();
+        variants.retain(|variant| seen.insert(variant.clone()));
+        variants.sort_by_key(|variant| Improv::distance(wanted, variant));
+        variants
+    }
+
+    /// The `distance` method returns the Levenshtein edit distance between `a` and `b`, counted in
+    /// characters.
+    pub fn distance(a: &str, b: &str) -> usize {
+        let b = b.chars().collect::<Vec<char>>();
+        let mut row = (0..=b.len()).collect::<Vec<usize>>();
+        for (i, ca) in a.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = if ca == *cb {
+                    diagonal
+                } else {
+                    1 + diagonal.min(above).min(row[j])
+                };
+                diagonal = above;
+            }
+        }
+        row[b.len()]
     }
 
-    /// The `passes` method calls [`PasswordGenerator::generate`] to produce a multiple passwords.
-    /// Commits a [`FauxPas`] if the [`passwords`] library returns an error, bubbling the message up.
-    /// The `pass` field is public to expose the configuration
+    /// The `pass` method produces a single password following the options in the `pass` field.
+    /// Commits a [`FauxPas`] if the options are invalid, bubbling the message from the
+    /// [`passwords`] library up.  The `pass` field is public to expose the configuration
     /// methods available in the [`passwords`] crate for the [`PasswordGenerator`].
-    pub fn passes(&self, count: usize) -> Polite<Vec<String>> {
-        let passes = self.pass.generate(count);
-        match passes {
-            Ok(values) => Ok(values),
-            Err(msg) => Err(FauxPas::Pass(msg.to_string())),
+    pub fn pass(&mut self) -> Polite<String> {
+        Ok(self.passes(1)?.remove(0))
+    }
+
+    /// The `passes` method produces `count` passwords following the options in the `pass` field.
+    /// Each character is drawn from the union of the enabled character sets.  When the `strict`
+    /// option is set, passwords missing any enabled set are drawn again.  Commits a [`FauxPas`]
+    /// if the [`passwords`] library rejects the options, bubbling the message up.
+    pub fn passes(&mut self, count: usize) -> Polite<Vec<String>> {
+        if let Err(msg) = self.pass.try_iter() {
+            return Err(FauxPas::Pass(msg.to_string()));
         }
+        let sets = Improv::charsets(&self.pass);
+        let pool = sets.concat();
+        let mut passes = Vec::with_capacity(count);
+        while passes.len() < count {
+            let pass = (0..self.pass.length)
+                .map(|_| pool[self.rng.gen_range(0..pool.len())])
+                .collect::<String>();
+            if !self.pass.strict || sets.iter().all(|set| pass.contains(set.as_slice())) {
+                passes.push(pass);
+            }
+        }
+        Self::tally("pass", passes.len());
+        Ok(passes)
+    }
+
+    /// The `charsets` method returns the character sets enabled by `config`, matching those of
+    /// the [`passwords`] crate.
+    fn charsets(config: &PasswordGenerator) -> Vec<Vec<char>> {
+        let similar = |c: &char| !config.exclude_similar_characters || !"01ilIoO\"'`|".contains(*c);
+        let mut sets = Vec::new();
+        let ranges = [
+            (config.numbers, '0'..='9'),
+            (config.lowercase_letters, 'a'..='z'),
+            (config.uppercase_letters, 'A'..='Z'),
+        ];
+        for (enabled, range) in ranges {
+            if enabled {
+                sets.push(range.filter(similar).collect());
+            }
+        }
+        if config.symbols {
+            sets.push(
+                ('!'..='~')
+                    .filter(|c| c.is_ascii_punctuation())
+                    .filter(similar)
+                    .collect(),
+            );
+        }
+        if config.spaces {
+            sets.push(vec![' ']);
+        }
+        sets
+    }
+
+    /// The `passphrase` method draws words from the EFF large wordlist to produce a passphrase
+    /// following the configuration in `config`.  Commits a [`FauxPas`] if the number of words is
+    /// outside the bounds in [`Passphrase::WORDS`].
+    pub fn passphrase(&mut self, config: &Passphrase) -> Polite<Phrase> {
+        if !Passphrase::WORDS.contains(&config.words) {
+            return Err(FauxPas::Pass(format!(
+                "Passphrase word count must be between {} and {}.",
+                Passphrase::WORDS.start(),
+                Passphrase::WORDS.end()
+            )));
+        }
+        let rng = &mut self.rng;
+        let list = Passphrase::wordlist();
+        let mut words = (0..config.words)
+            .map(|_| list[rng.gen_range(0..list.len())].to_string())
+            .collect::<Vec<String>>();
+        if config.capitalize {
+            words = words
+                .into_iter()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => word,
+                    }
+                })
+                .collect();
+        }
+        if config.digit {
+            let index = rng.gen_range(0..words.len());
+            words[index].push(char::from(b'0' + rng.gen_range(0..10)));
+        }
+        if config.symbol {
+            let symbols = Passphrase::SYMBOLS.chars().collect::<Vec<char>>();
+            let index = rng.gen_range(0..words.len());
+            words[index].push(symbols[rng.gen_range(0..symbols.len())]);
+        }
+        Self::tally("passphrase", 1);
+        Ok(Phrase {
+            passphrase: words.join(&config.separator),
+            entropy: config.entropy(),
+        })
     }
 
     /// The `guest` method creates a new [`Guest`] struct using an improvised name and pass.
@@ -85,7 +263,7 @@ impl<'a> Improv<'a> {
     pub fn guest(&mut self) -> Polite<Guest> {
         let name = self.name()?;
         let hash = self.pass()?;
-        Ok(Guest::new(&name, &hash))
+        Ok(self.enroll(&name, &hash))
     }
 
     /// The `guests` method creates a vector of type [`Guest`] and length `count`.  Passes any
@@ -94,24 +272,38 @@ impl<'a> Improv<'a> {
     pub fn guests(&mut self, count: usize) -> Polite<Vec<Guest>> {
         let names = self.names(count)?;
         let passes = self.passes(count)?;
-        let mut guests = Vec::new();
-        let mut i = 0;
-        while guests.len() < count {
-            guests.push(Guest::new(&names[i], &passes[i]));
-            i += 1;
+        Ok(names
+            .iter()
+            .zip(&passes)
+            .map(|(name, pass)| self.enroll(name, pass))
+            .collect())
+    }
+
+    /// Adds `count` to the [`Improv::GENERATED`] counter for `kind`.
+    fn tally(kind: &'static str, count: usize) {
+        metrics::counter!(Self::GENERATED, "kind" => kind).increment(count as u64);
+    }
+
+    /// Creates a [`Guest`] with a version 4 id drawn from the generator of the `Improv`, rather
+    /// than from the operating system as in [`Guest::new`].
+    fn enroll(&mut self, name: &str, pass: &str) -> Guest {
+        let id = uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid();
+        Self::tally("guest", 1);
+        Guest {
+            id,
+            ..Guest::new(name, pass)
         }
-        Ok(guests)
     }
 }
 
-impl<'a> Default for Improv<'a> {
+impl Default for Improv {
     fn default() -> Self {
         Self::new(true)
     }
 }
 
 /// The `Pass` struct holds configuration information for a [`PasswordGenerator`].
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Pass {
     /// Wrapper for the `length` field in [`PasswordGenerator`].
     pub length: usize,
@@ -132,11 +324,84 @@ pub struct Pass {
 }
 
 impl Pass {
+    /// The bounds on the `length` field.
+    pub const LENGTH: std::ops::RangeInclusive<usize> = 1..=128;
+
     /// Creates a new `Pass` struct from the default method.  Modify the fields directly after
     /// construction to customize.
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// The `classes` method returns the number of character classes enabled: numbers, lowercase
+    /// letters, uppercase letters, symbols and spaces.
+    pub fn classes(&self) -> usize {
+        [
+            self.numbers,
+            self.lowercase,
+            self.uppercase,
+            self.symbols,
+            self.spaces,
+        ]
+        .into_iter()
+        .filter(|enabled| *enabled)
+        .count()
+    }
+
+    /// The `validate` method checks the configuration before any password is generated.  The
+    /// `length` must fall within [`Pass::LENGTH`], at least one character class must be enabled,
+    /// and a `strict` configuration must be long enough to hold one character of each enabled
+    /// class.  Commits a [`FauxPas::Invalid`] listing every rule broken.
+    pub fn validate(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        if !Self::LENGTH.contains(&self.length) {
+            flaws.push(Flaw::new(
+                "length",
+                "range",
+                &format!(
+                    "Must be between {} and {}.",
+                    Self::LENGTH.start(),
+                    Self::LENGTH.end()
+                ),
+            ));
+        }
+        let classes = self.classes();
+        if classes == 0 {
+            flaws.push(Flaw::new(
+                "classes",
+                "required",
+                "Enable at least one of numbers, lowercase, uppercase, symbols or spaces.",
+            ));
+        }
+        if self.strict && self.length < classes {
+            flaws.push(Flaw::new(
+                "length",
+                "strict",
+                &format!(
+                    "Must be at least {} to include every enabled class in strict mode.",
+                    classes
+                ),
+            ));
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+}
+
+impl From<Pass> for PasswordGenerator {
+    fn from(pass: Pass) -> Self {
+        PasswordGenerator::new()
+            .length(pass.length)
+            .numbers(pass.numbers)
+            .lowercase_letters(pass.lowercase)
+            .uppercase_letters(pass.uppercase)
+            .symbols(pass.symbols)
+            .spaces(pass.spaces)
+            .exclude_similar_characters(pass.exclude)
+            .strict(pass.strict)
+    }
 }
 
 impl Default for Pass {
@@ -153,3 +418,80 @@ impl Default for Pass {
         }
     }
 }
+
+/// The `Passphrase` struct holds configuration for diceware-style passphrases, drawn from the
+/// EFF large wordlist.  Fields missing from a request body take their default values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct Passphrase {
+    /// The number of words in the passphrase.
+    pub words: usize,
+    /// The text placed between words.
+    pub separator: String,
+    /// Capitalize the first letter of each word.
+    pub capitalize: bool,
+    /// Append a random digit to a random word.
+    pub digit: bool,
+    /// Append a random symbol from [`Passphrase::SYMBOLS`] to a random word.
+    pub symbol: bool,
+}
+
+impl Passphrase {
+    /// The bounds on the number of words in a passphrase.
+    pub const WORDS: std::ops::RangeInclusive<usize> = 1..=64;
+    /// The symbols available when the `symbol` field is `true`.
+    pub const SYMBOLS: &'static str = "!#$%&*+=?@^~";
+
+    /// Creates a new `Passphrase` struct from the default method.  Modify the fields directly
+    /// after construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `wordlist` method returns the words of the embedded EFF large wordlist.
+    pub fn wordlist() -> &'static [&'static str] {
+        static WORDLIST: OnceLock<Vec<&'static str>> = OnceLock::new();
+        WORDLIST.get_or_init(|| {
+            include_str!("../data/eff_large_wordlist.txt")
+                .lines()
+                .filter_map(|line| line.split('\t').nth(1))
+                .collect()
+        })
+    }
+
+    /// The `entropy` method returns the entropy in bits of passphrases produced with this
+    /// configuration.  Each word contributes `log2` of the wordlist length, and the optional digit
+    /// and symbol contribute both their choice and their position.
+    pub fn entropy(&self) -> f64 {
+        let words = self.words as f64;
+        let mut entropy = words * (Self::wordlist().len() as f64).log2();
+        if self.digit {
+            entropy += 10f64.log2() + words.log2();
+        }
+        if self.symbol {
+            entropy += (Self::SYMBOLS.chars().count() as f64).log2() + words.log2();
+        }
+        entropy
+    }
+}
+
+impl Default for Passphrase {
+    fn default() -> Self {
+        Self {
+            words: 6,
+            separator: "-".to_string(),
+            capitalize: false,
+            digit: false,
+            symbol: false,
+        }
+    }
+}
+
+/// The `Phrase` struct holds a generated passphrase together with its entropy in bits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Phrase {
+    /// The generated passphrase.
+    pub passphrase: String,
+    /// The entropy of the passphrase configuration, in bits.
+    pub entropy: f64,
+}
@@ -0,0 +1,171 @@
+//! The `lexicon` module holds the [`Lexicon`] struct, a collection of named wordlists used to fill
+//! the placeholders of a [`crate::Pattern`], along with a blocklist of words that may not appear
+//! in improvised names.
+use cordial_guest::Guest;
+use polite::{FauxPas, Polite};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The `Lexicon` struct maps wordlist names to their words.  Words on the blocklist are dropped
+/// from every wordlist, and names containing them are rejected by [`Lexicon::clean`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lexicon {
+    lists: HashMap<String, Vec<String>>,
+    blocklist: HashSet<String>,
+}
+
+impl Lexicon {
+    /// The environmental variable naming a directory of wordlists loaded by
+    /// [`Lexicon::from_env`].
+    pub const WORDLISTS: &'static str = "IMPROV_WORDLISTS";
+    /// The name of the file in a wordlist directory that extends the blocklist.
+    pub const BLOCKLIST: &'static str = "blocklist";
+
+    /// Creates a `Lexicon` with no wordlists, using the embedded blocklist.
+    pub fn new() -> Self {
+        let mut lexicon = Self {
+            lists: HashMap::new(),
+            blocklist: HashSet::new(),
+        };
+        lexicon.block(Self::parse(include_str!("../data/blocklist.txt")));
+        lexicon
+    }
+
+    /// The `standard` method returns a shared copy of the default `Lexicon`, holding the
+    /// `adjective` and `noun` wordlists of the [`names`] crate.
+    pub fn standard() -> &'static Lexicon {
+        static STANDARD: OnceLock<Lexicon> = OnceLock::new();
+        STANDARD.get_or_init(Lexicon::default)
+    }
+
+    /// The `global` method returns a shared `Lexicon` built once by [`Lexicon::from_env`].
+    /// Commits a [`FauxPas`] if the wordlists could not be loaded.
+    pub fn global() -> Polite<&'static Lexicon> {
+        static GLOBAL: OnceLock<Result<Lexicon, String>> = OnceLock::new();
+        match GLOBAL.get_or_init(|| Lexicon::from_env().map_err(|e| e.to_string())) {
+            Ok(lexicon) => Ok(lexicon),
+            Err(msg) => Err(std::io::Error::other(msg.clone()).into()),
+        }
+    }
+
+    /// The `from_env` method creates the default `Lexicon`, then loads the directory named by
+    /// the [`Lexicon::WORDLISTS`] environmental variable if it is set.  See [`Lexicon::load_dir`].
+    pub fn from_env() -> Polite<Self> {
+        let mut lexicon = Self::default();
+        if let Ok(dir) = std::env::var(Self::WORDLISTS) {
+            lexicon.load_dir(dir)?;
+        }
+        Ok(lexicon)
+    }
+
+    /// The `parse` method reads words from `text`, one per line.  Blank lines and lines starting
+    /// with `#` are skipped, and surrounding whitespace is trimmed.
+    pub fn parse(text: &str) -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The `insert` method sets the wordlist called `list` to `words`, replacing any existing
+    /// list of that name.  Duplicates and words on the blocklist are left out.
+    pub fn insert<I, S>(&mut self, list: &str, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut seen = HashSet::new();
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().to_string())
+            .filter(|word| self.clean(word) && seen.insert(word.clone()))
+            .collect();
+        self.lists.insert(list.to_string(), words);
+    }
+
+    /// The `embed` method sets the wordlist called `list` from `text`, such as the contents of a
+    /// file included with [`include_str!`].  See [`Lexicon::parse`].
+    pub fn embed(&mut self, list: &str, text: &str) {
+        self.insert(list, Self::parse(text));
+    }
+
+    /// The `load` method sets the wordlist called `list` from the file at `path`.  Commits a
+    /// [`FauxPas`] if the file cannot be read.
+    pub fn load<P: AsRef<Path>>(&mut self, list: &str, path: P) -> Polite<()> {
+        let text = std::fs::read_to_string(path)?;
+        self.embed(list, &text);
+        Ok(())
+    }
+
+    /// The `load_dir` method loads every `.txt` file in `dir` as a wordlist named after the file
+    /// stem, so `colors.txt` fills the `{colors}` placeholder.  A file called `blocklist.txt`
+    /// extends the blocklist instead.  Commits a [`FauxPas`] if the directory or a file cannot be
+    /// read.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Polite<()> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        let (blocklists, wordlists): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|path| path.file_stem().is_some_and(|stem| stem == Self::BLOCKLIST));
+        for path in blocklists {
+            self.block(Self::parse(&std::fs::read_to_string(path)?));
+        }
+        for path in wordlists {
+            let stem = match path.file_stem() {
+                Some(stem) => stem.to_owned(),
+                None => continue,
+            };
+            match stem.to_str() {
+                Some(list) => self.load(list, &path)?,
+                None => return Err(FauxPas::FileName(stem)),
+            }
+        }
+        Ok(())
+    }
+
+    /// The `block` method adds `words` to the blocklist and removes them from every wordlist.
+    pub fn block<I, S>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.blocklist
+            .extend(words.into_iter().map(|word| Guest::fold(word.as_ref())));
+        let mut lists = std::mem::take(&mut self.lists);
+        lists
+            .values_mut()
+            .for_each(|words| words.retain(|word| self.clean(word)));
+        self.lists = lists;
+    }
+
+    /// The `words` method returns the wordlist called `list`, if present.
+    pub fn words(&self, list: &str) -> Option<&[String]> {
+        self.lists.get(list).map(Vec::as_slice)
+    }
+
+    /// The `clean` method returns `true` if no word of `name` is on the blocklist.  Words are the
+    /// runs of letters and digits in `name`, compared after case folding.  See [`Guest::fold`].
+    pub fn clean(&self, name: &str) -> bool {
+        Guest::fold(name)
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .all(|word| !self.blocklist.contains(word))
+    }
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        let mut lexicon = Self::new();
+        lexicon.insert("adjective", names::ADJECTIVES);
+        lexicon.insert("noun", names::NOUNS);
+        lexicon
+    }
+}
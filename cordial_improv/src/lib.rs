@@ -1,3 +1,13 @@
+pub mod bulk;
 pub mod improv;
+pub mod lexicon;
+pub mod pattern;
+pub mod policy;
+pub mod vacancy;
 
-pub use crate::improv::{Improv, Pass};
+pub use crate::bulk::Bulk;
+pub use crate::improv::{Improv, Pass, Passphrase, Phrase};
+pub use crate::lexicon::Lexicon;
+pub use crate::pattern::{Pattern, Piece};
+pub use crate::policy::{Appraisal, Candidate, Policy};
+pub use crate::vacancy::Vacancy;
@@ -0,0 +1,194 @@
+//! The `pattern` module holds the [`Pattern`] struct, a template for improvised names such as
+//! `{adjective}-{noun}-{digits:4}`.
+use crate::lexicon::Lexicon;
+use polite::{FauxPas, Flaw, Polite};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The `Piece` enum holds one part of a parsed [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    /// Text copied into the name as written.
+    Text(String),
+    /// A random word from the [`Lexicon`] wordlist of the given name.
+    Word(String),
+    /// The given number of random decimal digits.
+    Digits(usize),
+}
+
+/// The `Pattern` struct is a parsed name template.  Placeholders in braces are replaced when the
+/// pattern is rendered, and all other text is copied as written:
+///
+/// * `{digits}` or `{digits:N}` becomes four or `N` random digits, with `N` from 1 to
+///   [`Pattern::MAX_DIGITS`].
+/// * `{list}` becomes a random word from the wordlist called `list` in the [`Lexicon`], such as
+///   the built-in `{adjective}` and `{noun}`.
+/// * `{{` and `}}` stand for literal braces.
+///
+/// Serializes as the template string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern {
+    template: String,
+    pieces: Vec<Piece>,
+}
+
+impl Pattern {
+    /// The template of the [`names::Name::Plain`] naming pattern.
+    pub const PLAIN: &'static str = "{adjective}-{noun}";
+    /// The template of the [`names::Name::Numbered`] naming pattern.
+    pub const NUMBERED: &'static str = "{adjective}-{noun}-{digits:4}";
+    /// The largest number of digits in a `{digits:N}` placeholder.
+    pub const MAX_DIGITS: usize = 16;
+
+    /// The `naming` method returns [`Pattern::NUMBERED`] if `numbered` is `true`, and
+    /// [`Pattern::PLAIN`] if `false`.
+    pub fn naming(numbered: bool) -> Self {
+        let template = match numbered {
+            true => Self::NUMBERED,
+            false => Self::PLAIN,
+        };
+        template.parse().expect("built-in name patterns are valid")
+    }
+
+    /// The `template` method returns the template the `Pattern` was parsed from.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// The `pieces` method returns the parts of the parsed template, in order.
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// The `render` method fills the placeholders of the `Pattern` using words from `lexicon` and
+    /// digits from `rng`.  Commits a [`FauxPas::Invalid`] if the template names a wordlist that
+    /// is missing from `lexicon` or empty.
+    pub fn render<R: Rng + ?Sized>(&self, lexicon: &Lexicon, rng: &mut R) -> Polite<String> {
+        let mut name = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => name.push_str(text),
+                Piece::Word(list) => {
+                    let word = lexicon
+                        .words(list)
+                        .and_then(|words| words.choose(rng))
+                        .ok_or_else(|| {
+                            Self::flaw("wordlist", &format!("No words in list {}.", list))
+                        })?;
+                    name.push_str(word);
+                }
+                Piece::Digits(count) => {
+                    (0..*count).for_each(|_| name.push(char::from(b'0' + rng.gen_range(0..10))))
+                }
+            }
+        }
+        Ok(name)
+    }
+
+    fn flaw(rule: &str, message: &str) -> FauxPas {
+        FauxPas::Invalid(vec![Flaw::new("pattern", rule, message)])
+    }
+
+    fn placeholder(inner: &str) -> Polite<Piece> {
+        let (key, arg) = match inner.split_once(':') {
+            Some((key, arg)) => (key.trim(), Some(arg.trim())),
+            None => (inner.trim(), None),
+        };
+        match (key, arg) {
+            ("", _) => Err(Self::flaw("syntax", "Empty placeholder {}.")),
+            ("digits", None) => Ok(Piece::Digits(4)),
+            ("digits", Some(arg)) => match arg.parse::<usize>() {
+                Ok(n) if (1..=Self::MAX_DIGITS).contains(&n) => Ok(Piece::Digits(n)),
+                _ => Err(Self::flaw(
+                    "digits",
+                    &format!(
+                        "Digit count must be between 1 and {}, found {}.",
+                        Self::MAX_DIGITS,
+                        arg
+                    ),
+                )),
+            },
+            (key, None) => Ok(Piece::Word(key.to_string())),
+            (key, Some(_)) => Err(Self::flaw(
+                "syntax",
+                &format!("Placeholder {{{}}} takes no argument.", key),
+            )),
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = FauxPas;
+
+    /// Parses a template.  Commits a [`FauxPas::Invalid`] if a brace is unmatched, a placeholder
+    /// is empty, or a digit count is out of bounds.
+    fn from_str(template: &str) -> Polite<Self> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => {
+                                return Err(Self::flaw("syntax", "Unclosed placeholder."))
+                            }
+                            Some(c) => inner.push(c),
+                        }
+                    }
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Self::placeholder(&inner)?);
+                }
+                '}' => return Err(Self::flaw("syntax", "Unmatched closing brace.")),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        if pieces.is_empty() {
+            return Err(Self::flaw("syntax", "Pattern is empty."));
+        }
+        Ok(Self {
+            template: template.to_string(),
+            pieces,
+        })
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = FauxPas;
+
+    fn try_from(template: String) -> Polite<Self> {
+        template.parse()
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.template
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
@@ -0,0 +1,134 @@
+//! The `policy` module holds the [`Policy`] struct, which scores candidate passwords using the
+//! analyzer and scorer of the [`passwords`] crate and enforces rules on them.
+use cordial_guest::Guest;
+use passwords::{analyzer, scorer};
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The `Policy` struct holds the rules a password must follow.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// The minimum score from [`passwords::scorer::score`], from 0 to 100.
+    pub min_score: f64,
+    /// The minimum number of characters.
+    pub min_length: usize,
+    /// Reject passwords found in the common password list of the [`passwords`] crate.
+    pub forbid_common: bool,
+    /// Reject passwords containing the name of the guest, ignoring case.
+    pub forbid_name: bool,
+}
+
+impl Policy {
+    /// Creates a new `Policy` struct from the default method.  Modify the fields directly after
+    /// construction to customize.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `appraise` method scores `pass`, returning the score, a rating and a [`Flaw`] for each
+    /// rule broken.  The name check is skipped if `name` is `None`.  The flaws refer to the
+    /// `field` holding the password.
+    pub fn appraise(&self, field: &str, name: Option<&str>, pass: &str) -> Appraisal {
+        let analyzed = analyzer::analyze(pass);
+        let score = scorer::score(&analyzed);
+        let mut flaws = Vec::new();
+        if analyzed.length() < self.min_length {
+            flaws.push(Flaw::new(
+                field,
+                "min_length",
+                &format!("Must be at least {} characters long.", self.min_length),
+            ));
+        }
+        if self.forbid_common && analyzed.is_common() {
+            flaws.push(Flaw::new(
+                field,
+                "common",
+                "Must not be a commonly used password.",
+            ));
+        }
+        if let Some(name) = name {
+            let name = Guest::fold(name);
+            if self.forbid_name && !name.is_empty() && Guest::fold(pass).contains(&name) {
+                flaws.push(Flaw::new(field, "name", "Must not contain the name."));
+            }
+        }
+        if score < self.min_score {
+            flaws.push(Flaw::new(
+                field,
+                "min_score",
+                &format!(
+                    "Scored {:.0}, below the minimum of {:.0}.  Add length or mix in numbers, \
+                     uppercase letters and symbols.",
+                    score, self.min_score
+                ),
+            ));
+        }
+        Appraisal {
+            score,
+            rating: Appraisal::rating(score).to_string(),
+            flaws,
+        }
+    }
+
+    /// The `check` method enforces the `Policy` on the password `pass` of a guest called `name`.
+    /// Commits a [`FauxPas::Invalid`] listing every rule broken, referring to the `field` holding
+    /// the password.
+    pub fn check(&self, field: &str, name: &str, pass: &str) -> Polite<()> {
+        let appraisal = self.appraise(field, Some(name), pass);
+        match appraisal.flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(appraisal.flaws)),
+        }
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            min_score: 40.0,
+            min_length: 8,
+            forbid_common: true,
+            forbid_name: true,
+        }
+    }
+}
+
+/// The `Candidate` struct holds a password to appraise, with the optional name of its owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Candidate {
+    /// The password to appraise.
+    pub pass: String,
+    /// The name of the guest, used to check for the name in the password.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// The `Appraisal` struct holds the result of [`Policy::appraise`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Appraisal {
+    /// The score from [`passwords::scorer::score`], from 0 to 100.
+    pub score: f64,
+    /// A description of the score, from "very dangerous" to "invulnerable".
+    pub rating: String,
+    /// The rules broken by the password, empty if it passes the [`Policy`].
+    pub flaws: Vec<Flaw>,
+}
+
+impl Appraisal {
+    /// The `rating` method describes a `score` using the bands documented by the [`passwords`]
+    /// crate.
+    pub fn rating(score: f64) -> &'static str {
+        match score {
+            s if s < 20.0 => "very dangerous",
+            s if s < 40.0 => "dangerous",
+            s if s < 60.0 => "very weak",
+            s if s < 80.0 => "weak",
+            s if s < 90.0 => "good",
+            s if s < 95.0 => "strong",
+            s if s < 99.0 => "very strong",
+            _ => "invulnerable",
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! The `vacancy` module holds the [`Vacancy`] struct, which improvises names that are not yet
+//! taken according to a [`Roster`].
+use crate::bulk::Bulk;
+use crate::improv::Improv;
+use crate::lexicon::Lexicon;
+use crate::pattern::Pattern;
+use cordial_memory::Roster;
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+/// The `Vacancy` struct holds parameters for improvising names, and methods for checking them
+/// against a [`Roster`].  Deserializes from the query string of the `/improv/name` route.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+pub struct Vacancy {
+    /// The `available` field indicates whether names should be checked against the [`Roster`].
+    pub available: bool,
+    /// The `numbered` field selects the [`names::Name::Numbered`] naming pattern.
+    pub numbered: bool,
+    /// The `pattern` field holds a name template, such as `{adjective}-{noun}-{digits:4}`.
+    /// Overrides `numbered` when present.  Wordlists come from [`Lexicon::global`].  See
+    /// [`Pattern`].
+    pub pattern: Option<String>,
+    /// The `near` field holds a requested name.  When present, suggestions resemble this name.
+    pub near: Option<String>,
+    /// The `count` field holds the number of names requested, from 1 to [`Vacancy::MAX`],
+    /// defaulting to one.
+    pub count: Option<usize>,
+}
+
+impl Vacancy {
+    /// The number of batches of random names to check before falling back to a numeric suffix.
+    pub const ATTEMPTS: usize = 4;
+    /// The number of names checked against the [`Roster`] in each round.
+    pub const BATCH: usize = 8;
+    /// The largest number of names in a single request, as for [`Bulk::MAX`].
+    pub const MAX: usize = Bulk::MAX;
+
+    /// Creates a new `Vacancy` that checks names for availability.  Modify the fields directly
+    /// after construction to customize.
+    pub fn new() -> Self {
+        Self {
+            available: true,
+            ..Default::default()
+        }
+    }
+
+    /// The `count` method returns the number of names requested, defaulting to one.
+    pub fn count(&self) -> usize {
+        self.count.unwrap_or(1)
+    }
+
+    /// The `validate` method checks that the [`Vacancy::count`] is between 1 and
+    /// [`Vacancy::MAX`].  Commits a [`FauxPas::Invalid`] otherwise.
+    pub fn validate(&self) -> Polite<()> {
+        match (1..=Self::MAX).contains(&self.count()) {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(vec![Flaw::new(
+                "count",
+                "range",
+                &format!("Must be between 1 and {}.", Self::MAX),
+            )])),
+        }
+    }
+
+    /// The `improvise` method returns `count` names following the `pattern` field, or the naming
+    /// pattern selected by `numbered` if absent, without checking their availability.  Commits a
+    /// [`FauxPas`] if the pattern is invalid or names a missing wordlist.
+    pub fn improvise(&self, count: usize) -> Polite<Vec<String>> {
+        let mut improv = Improv::new(self.numbered);
+        match &self.pattern {
+            Some(template) => {
+                let pattern = template.parse::<Pattern>()?;
+                improv.patterns(&pattern, Lexicon::global()?, count)
+            }
+            None => improv.names(count),
+        }
+    }
+
+    /// The `name` method improvises a name that is not taken in `roster`.  Checks batches of
+    /// random names up to [`Vacancy::ATTEMPTS`] times, then appends increasing numeric suffixes
+    /// to a random name.  Commits a [`FauxPas`] if no vacant name turns up.
+    pub async fn name<R: Roster + ?Sized>(&self, roster: &R) -> Polite<String> {
+        self.pick(roster, &[]).await
+    }
+
+    /// Improvises a name that is neither taken in `roster` nor among `chosen`, checking at most
+    /// twice [`Vacancy::ATTEMPTS`] batches as for [`Vacancy::name`].
+    async fn pick<R: Roster + ?Sized>(&self, roster: &R, chosen: &[String]) -> Polite<String> {
+        let fresh = |names: Vec<String>| names.into_iter().find(|name| !chosen.contains(name));
+        for _ in 0..Self::ATTEMPTS {
+            let batch = self.improvise(Self::BATCH)?;
+            if let Some(name) = fresh(roster.vacant(&batch).await?) {
+                return Ok(name);
+            }
+        }
+        let base = self.improvise(1)?.remove(0);
+        for round in 0..Self::ATTEMPTS {
+            let batch = (0..Self::BATCH)
+                .map(|n| format!("{}-{}", base, round * Self::BATCH + n + 2))
+                .collect::<Vec<String>>();
+            if let Some(name) = fresh(roster.vacant(&batch).await?) {
+                return Ok(name);
+            }
+        }
+        Err(FauxPas::Improv("No vacant name found.".to_string()))
+    }
+
+    /// The `suggest` method returns up to [`Vacancy::count`] names resembling `wanted` that are
+    /// not taken in `roster`, closest first.  Widens the search each round, for up to
+    /// [`Vacancy::ATTEMPTS`] rounds.  See [`Improv::variants`].  Commits a [`FauxPas::Invalid`]
+    /// if the count fails [`Vacancy::validate`].
+    pub async fn suggest<R: Roster + ?Sized>(
+        &self,
+        roster: &R,
+        wanted: &str,
+    ) -> Polite<Vec<String>> {
+        self.validate()?;
+        let count = self.count();
+        let mut suggestions: Vec<String> = Vec::new();
+        for round in 0..Self::ATTEMPTS {
+            let variants = Improv::new(self.numbered).variants(wanted, count << round);
+            for name in roster.vacant(&variants).await? {
+                if suggestions.len() < count && !suggestions.contains(&name) {
+                    suggestions.push(name);
+                }
+            }
+            if suggestions.len() == count {
+                break;
+            }
+        }
+        Ok(suggestions)
+    }
+
+    /// The `offer` method returns [`Vacancy::count`] names according to the fields of the
+    /// `Vacancy`.  Names resemble the `near` field if present, and are checked against `roster`
+    /// if `available` is `true`.  Commits a [`FauxPas::Invalid`] before improvising any names if
+    /// the count fails [`Vacancy::validate`], and after if fewer distinct vacant names turn up
+    /// than were requested.
+    pub async fn offer<R: Roster + ?Sized>(&self, roster: &R) -> Polite<Vec<String>> {
+        self.validate()?;
+        let count = self.count();
+        match (&self.near, self.available) {
+            (Some(wanted), true) => self.suggest(roster, wanted).await,
+            (Some(wanted), false) => {
+                let mut variants = Improv::new(self.numbered).variants(wanted, count);
+                variants.truncate(count);
+                Ok(variants)
+            }
+            (None, true) => {
+                let mut names: Vec<String> = Vec::with_capacity(count);
+                for _ in 0..count {
+                    match self.pick(roster, &names).await {
+                        Ok(name) => names.push(name),
+                        Err(FauxPas::Improv(_)) => {
+                            return Err(FauxPas::Invalid(vec![Flaw::new(
+                                "count",
+                                "vacancy",
+                                &format!(
+                                    "Found only {} of {} vacant names for the pattern.",
+                                    names.len(),
+                                    count
+                                ),
+                            )]))
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(names)
+            }
+            (None, false) => self.improvise(count),
+        }
+    }
+}
@@ -1,3 +1,3 @@
 pub mod memory;
 
-pub use crate::memory::Memorable;
+pub use crate::memory::{Memorable, Roster};
@@ -1,5 +1,6 @@
 //! The `memory` crate provides the [`Memorable`] trait, which enables methods for persisting data
-//! in a database using a standard CRUD API.
+//! in a database using a standard CRUD API, and the [`Roster`] trait, which reports whether names
+//! are already in use.
 use polite::Polite;
 use uuid::Uuid;
 
@@ -12,4 +13,30 @@ pub trait Memorable<T>: Send + Sync + 'static {
     async fn create(&self, mem: &T) -> Polite<T>;
     async fn update(&self, mem: &T) -> Polite<T>;
     async fn delete(&self, mem: &T) -> Polite<()>;
+    /// Creates every entry in `mems` as a single unit, returning the created entries in the same
+    /// order.  Either all entries are created, or none are.
+    async fn create_many(&self, mems: &[T]) -> Polite<Vec<T>>;
+    /// Updates every entry in `mems` as a single unit, returning the updated entries in the same
+    /// order.  Either all entries are updated, or none are.
+    async fn update_many(&self, mems: &[T]) -> Polite<Vec<T>>;
+    /// Deletes every entry in `mems` as a single unit.
+    async fn delete_many(&self, mems: &[T]) -> Polite<()>;
+}
+
+/// The `Roster` trait defines a protocol for asking a store whether names are already taken.
+#[async_trait::async_trait]
+pub trait Roster: Send + Sync + 'static {
+    /// Returns `true` if `name` is already taken.
+    async fn taken(&self, name: &str) -> Polite<bool>;
+    /// Returns the entries of `names` that are not taken, in their original order.  The default
+    /// implementation calls [`Roster::taken`] once per name.
+    async fn vacant(&self, names: &[String]) -> Polite<Vec<String>> {
+        let mut vacant = Vec::new();
+        for name in names {
+            if !self.taken(name).await? {
+                vacant.push(name.clone());
+            }
+        }
+        Ok(vacant)
+    }
 }
@@ -0,0 +1,6 @@
+CREATE TABLE seeds (
+    seed BIGINT PRIMARY KEY,
+    count INTEGER NOT NULL,
+    pass TEXT NOT NULL,
+    planted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,6 @@
+-- Applied once `Posture::migrate` has filled in every key.  Guests whose names fold to the same
+-- key must be renamed first; see `Posture::fold_names`.
+ALTER TABLE guests
+    ALTER COLUMN name_key SET NOT NULL;
+
+CREATE UNIQUE INDEX guests_name_key_idx ON guests (name_key);
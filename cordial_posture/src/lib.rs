@@ -1,3 +1,4 @@
 pub mod posture;
 
-pub use crate::posture::Posture;
+pub use crate::posture::{Posture, MIGRATOR};
+pub use cordial_guest::Milestone;
@@ -1,16 +1,43 @@
 //! The `posture` crate contains the database configuration "posture" of the host. Currently
 //! supports local postgres hosting.
+use cordial_guest::{Guest, Milestone};
 use dotenvy::dotenv;
-use polite::Polite;
+use polite::{FauxPas, Flaw, Polite};
 use secrecy::ExposeSecret;
 use secrecy::Secret;
 use serde::Deserialize;
 use serde_aux::field_attributes::deserialize_number_from_string;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{Migration, MigrationSource, Migrator};
 use sqlx::postgres::{PgConnectOptions, PgSslMode};
 use sqlx::ConnectOptions;
 use sqlx::{postgres::PgPoolOptions, Connection, Executor, PgConnection, PgPool};
+use std::future::Future;
+use std::pin::Pin;
 use std::time::Duration;
 use tracing::trace;
+use uuid::Uuid;
+
+/// The migrations in the `migrations` directory of the crate, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// The `Stage` struct resolves the migrations of [`MIGRATOR`] up to and including a version, so
+/// that [`Posture::migrate`] can run them in steps.
+#[derive(Debug)]
+struct Stage(i64);
+
+type Migrations = Result<Vec<Migration>, BoxDynError>;
+
+impl MigrationSource<'static> for Stage {
+    fn resolve(self) -> Pin<Box<dyn Future<Output = Migrations> + Send>> {
+        let migrations = MIGRATOR
+            .iter()
+            .filter(|migration| migration.version <= self.0)
+            .cloned()
+            .collect();
+        Box::pin(async move { Ok(migrations) })
+    }
+}
 
 /// The `Posture` struct contains fields and methods for managing database configuration.
 #[derive(Debug, Clone, Deserialize)]
@@ -28,12 +55,21 @@ pub struct Posture {
     pub database: String,
     /// The `ssl` field indicates whether to require an SSL connection.
     pub ssl: bool,
+    /// The `retention_days` field holds the number of days a deleted guest keeps its name
+    /// reserved before becoming eligible for purging.
+    #[serde(default = "Posture::retention_days")]
+    pub retention_days: u64,
 }
 
 impl Posture {
+    /// The version of the migration adding the `name_key` column, filled in by
+    /// [`Posture::fold_names`] before the key is made required and unique.
+    pub const NAME_KEY: i64 = 20261020;
+
     /// The `from_env` method politely attempts to create a new `Posture` from the `.env` file in the
     /// working directory.  Commits a [`FauxPas`] if `.env` is not present, or if the variables
-    /// `DB_USERNAME`, `DB_PASSWORD`, `DB_HOST` and `DB_NAME` are not present.
+    /// `DB_USERNAME`, `DB_PASSWORD`, `DB_HOST` and `DB_NAME` are not present.  The optional
+    /// variable `DB_RETENTION_DAYS` overrides the default retention period of thirty days.
     pub fn from_env() -> Polite<Self> {
         dotenv().ok();
         let name = std::env::var("DB_USERNAME")?;
@@ -42,6 +78,10 @@ impl Posture {
         let port = 5432;
         let database = std::env::var("DB_NAME")?;
         let ssl = false;
+        let retention_days = match std::env::var("DB_RETENTION_DAYS") {
+            Ok(days) => days.parse()?,
+            Err(_) => Self::retention_days(),
+        };
         Ok(Self {
             name,
             pass,
@@ -49,9 +89,19 @@ impl Posture {
             port,
             database,
             ssl,
+            retention_days,
         })
     }
 
+    fn retention_days() -> u64 {
+        30
+    }
+
+    /// The `retention` method returns the retention period for deleted guests as a [`Duration`].
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_days * 24 * 60 * 60)
+    }
+
     /// Reveals the connection string for the database.
     pub fn introduction(&self) -> Secret<String> {
         Secret::new(format!(
@@ -111,13 +161,142 @@ impl Posture {
         Ok(())
     }
 
+    /// The `migrate` method applies the migrations in [`MIGRATOR`] not yet applied to the
+    /// database.  Migrations run up to [`Posture::NAME_KEY`] first, then [`Posture::fold_names`]
+    /// fills in the name keys, then the rest run.  Commits a [`FauxPas`] if unable to connect
+    /// with the database, if a migration fails, or if two guests share a name key.
     pub async fn migrate(&self) -> Polite<()> {
         let connection_pool = PgPool::connect_with(self.database()).await?;
+        self.migrate_to(Self::NAME_KEY).await?;
+        Self::fold_names(&connection_pool).await?;
         trace!("Migrating database.");
-        sqlx::migrate!("./migrations").run(&connection_pool).await?;
+        MIGRATOR.run(&connection_pool).await?;
+        Ok(())
+    }
+
+    /// The `migrate_to` method applies the migrations in [`MIGRATOR`] up to and including
+    /// `version`, leaving later ones for [`Posture::migrate`].  Commits a [`FauxPas`] if unable
+    /// to connect with the database or if a migration fails.
+    pub async fn migrate_to(&self, version: i64) -> Polite<()> {
+        let connection_pool = PgPool::connect_with(self.database()).await?;
+        trace!("Migrating database to {}.", version);
+        let mut staged = Migrator::new(Stage(version)).await?;
+        staged.set_ignore_missing(true);
+        staged.run(&connection_pool).await?;
+        Ok(())
+    }
+
+    /// The `fold_names` method fills in the `name_key` of each guest without one with
+    /// [`Guest::fold`], for guests entered before the keys were added.  The keys are only saved
+    /// if no two guests share one, since the unique index on `name_key` would refuse them.
+    /// Otherwise commits a [`FauxPas::Invalid`] with a [`Flaw`] listing the ids of each set of
+    /// guests sharing a key.  To resolve, rename all but one guest of each set, for example with
+    /// `UPDATE guests SET name = 'alice 2' WHERE id = '...'`, and migrate again.
+    pub async fn fold_names(pool: &PgPool) -> Polite<()> {
+        let mut tx = pool.begin().await?;
+        let guests = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, name FROM guests WHERE name_key IS NULL FOR UPDATE",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if guests.is_empty() {
+            return Ok(());
+        }
+        trace!("Folding {} guest names.", guests.len());
+        let (ids, keys): (Vec<Uuid>, Vec<String>) = guests
+            .iter()
+            .map(|(id, name)| (*id, Guest::fold(name)))
+            .unzip();
+        sqlx::query(
+            r#"
+      UPDATE guests
+      SET name_key = folded.key
+      FROM UNNEST($1::uuid[], $2::text[]) AS folded (id, key)
+      WHERE guests.id = folded.id
+            "#,
+        )
+        .bind(&ids)
+        .bind(&keys)
+        .execute(&mut *tx)
+        .await?;
+        let shared = sqlx::query_as::<_, (String, Vec<Uuid>)>(
+            r#"
+      SELECT name_key, array_agg(id ORDER BY id)
+      FROM guests
+      GROUP BY name_key
+      HAVING count(*) > 1
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if !shared.is_empty() {
+            let flaws = shared
+                .iter()
+                .map(|(key, ids)| {
+                    let ids = ids.iter().map(Uuid::to_string).collect::<Vec<_>>();
+                    let message = format!(
+                        "Guests {} share the name key {}.  Rename all but one and migrate again.",
+                        ids.join(", "),
+                        key
+                    );
+                    Flaw::new("name", "unique", &message)
+                })
+                .collect();
+            return Err(FauxPas::Invalid(flaws));
+        }
+        tx.commit().await?;
         Ok(())
     }
 
+    /// The `exists` method returns `true` if the database named in the `Posture` exists.  Commits
+    /// a [`polite::FauxPas`] if unable to connect with Postgres.
+    pub async fn exists(&self) -> Polite<bool> {
+        trace!("Checking for database {}.", &self.database);
+        let mut connection = PgConnection::connect_with(&self.connect()).await?;
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)",
+        )
+        .bind(&self.database)
+        .fetch_one(&mut connection)
+        .await?;
+        Ok(exists)
+    }
+
+    /// The `status` method lists each migration embedded in the crate, marking those applied to
+    /// the database.  Commits a [`polite::FauxPas`] if unable to connect with the database.
+    pub async fn status(&self) -> Polite<Vec<Milestone>> {
+        let mut connection = PgConnection::connect_with(&self.database()).await?;
+        Self::survey(&mut connection).await
+    }
+
+    /// The `survey` method lists each migration in [`MIGRATOR`], marking those recorded as applied
+    /// in the migrations table of the database behind `connection`.  Reports every migration as
+    /// pending if the table does not exist yet.
+    pub async fn survey(connection: &mut PgConnection) -> Polite<Vec<Milestone>> {
+        let tracked =
+            sqlx::query_scalar::<_, bool>("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .fetch_one(&mut *connection)
+                .await?;
+        let applied = match tracked {
+            true => {
+                sqlx::query_scalar::<_, i64>(
+                    "SELECT version FROM _sqlx_migrations WHERE success ORDER BY version",
+                )
+                .fetch_all(&mut *connection)
+                .await?
+            }
+            false => Vec::new(),
+        };
+        Ok(MIGRATOR
+            .iter()
+            .map(|migration| Milestone {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: applied.contains(&migration.version),
+            })
+            .collect())
+    }
+
     pub async fn delete(&self) -> Polite<()> {
         trace!("Deleting database {}.", &self.database);
         let mut connection = PgConnection::connect_with(&self.connect()).await?;
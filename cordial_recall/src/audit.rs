@@ -0,0 +1,263 @@
+//! The `audit` module records the append-only trail of changes made to [`Guest`] entries through
+//! a [`crate::Recall`] or [`crate::Ledger`], each change an [`Audit`] entry, along with the
+//! [`Witness`] recording who made them.  The changing operations are written here against a single
+//! [`PgConnection`], each in a nested transaction with its audit, so both stores record the same
+//! trail.
+use crate::query;
+use cordial_guest::{Action, Audit, Guest};
+use polite::Polite;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, PgConnection};
+use std::time::Duration;
+use tracing::trace;
+use uuid::Uuid;
+
+/// The `Witness` struct holds who is making changes through a [`crate::Recall`], written to each
+/// [`Audit`] it records.  Attach one with [`crate::Recall::attended`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Witness {
+    /// The `actor` field names who is making the change.  The server does not authenticate
+    /// callers, so over HTTP this is the identity the caller claims.
+    pub actor: String,
+    /// The `request_id` field holds the id of the request making the change, if any.
+    pub request_id: Option<String>,
+    /// The `ip` field holds the address of the client making the change, if known.
+    pub ip: Option<String>,
+}
+
+impl Witness {
+    /// The actor recorded when none is given, such as for changes made from the command line.
+    pub const SYSTEM: &'static str = "system";
+
+    /// Creates a `Witness` for `actor`, with no request id or address.  Modify the fields
+    /// directly after construction to customize.
+    pub fn new(actor: &str) -> Self {
+        Self {
+            actor: actor.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Witness {
+    fn default() -> Self {
+        Self {
+            actor: Self::SYSTEM.to_string(),
+            request_id: None,
+            ip: None,
+        }
+    }
+}
+
+/// The `Change` struct pairs the states of one guest before and after an [`Action`], the input
+/// to [`record`].
+pub(crate) struct Change<'a> {
+    pub(crate) action: Action,
+    pub(crate) guest_id: Uuid,
+    pub(crate) before: Option<&'a Guest>,
+    pub(crate) after: Option<&'a Guest>,
+}
+
+impl<'a> Change<'a> {
+    pub(crate) fn new(action: Action, before: Option<&'a Guest>, after: Option<&'a Guest>) -> Self {
+        let guest_id = after.or(before).map(|guest| guest.id).unwrap_or_default();
+        Self {
+            action,
+            guest_id,
+            before,
+            after,
+        }
+    }
+
+    /// Pairs each guest in `after` with its state in `before`, matched by `id`.
+    pub(crate) fn pairs(
+        action: Action,
+        before: &'a [Guest],
+        after: &'a [Guest],
+    ) -> Vec<Change<'a>> {
+        let mut changes = before
+            .iter()
+            .map(|old| {
+                let new = after.iter().find(|guest| guest.id == old.id);
+                Self::new(action, Some(old), new)
+            })
+            .collect::<Vec<_>>();
+        changes.extend(
+            after
+                .iter()
+                .filter(|new| !before.iter().any(|old| old.id == new.id))
+                .map(|new| Self::new(action, None, Some(new))),
+        );
+        changes
+    }
+}
+
+/// Appends an [`Audit`] for each of `changes` made by `witness`, on the connection holding the
+/// changes so that both commit or roll back together.
+pub(crate) async fn record(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    changes: &[Change<'_>],
+) -> Polite<()> {
+    trace!("Recording {} audits for {}.", changes.len(), witness.actor);
+    let mut ids = Vec::with_capacity(changes.len());
+    let mut actions = Vec::with_capacity(changes.len());
+    let mut diffs = Vec::with_capacity(changes.len());
+    for change in changes {
+        ids.push(change.guest_id);
+        actions.push(change.action);
+        diffs.push(Audit::diff(change.before, change.after)?);
+    }
+    sqlx::query::<_>(
+        r#"
+      INSERT INTO audits (actor, guest_id, action, diff, request_id, ip)
+      SELECT $1, batch.guest_id, batch.action, batch.diff, $2, $3
+      FROM UNNEST($4::uuid[], $5::text[], $6::jsonb[]) WITH ORDINALITY
+        AS batch (guest_id, action, diff, rank)
+      ORDER BY batch.rank
+      "#,
+    )
+    .bind(&witness.actor)
+    .bind(&witness.request_id)
+    .bind(&witness.ip)
+    .bind(&ids)
+    .bind(&actions)
+    .bind(&diffs)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Creates `mem`, recording an [`Action::Create`] made by `witness`.
+pub(crate) async fn create(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    mem: &Guest,
+    retention: Duration,
+) -> Polite<Guest> {
+    let mut tx = conn.begin().await?;
+    free(&mut tx, witness, &[mem.key()], retention).await?;
+    let created = query::create(&mut tx, mem).await?;
+    let changes = Change::pairs(Action::Create, &[], std::slice::from_ref(&created));
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(created)
+}
+
+/// Updates `mem`, recording an [`Action::Update`] made by `witness`.
+pub(crate) async fn update(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    mem: &Guest,
+) -> Polite<Guest> {
+    let mut tx = conn.begin().await?;
+    let before = query::snapshot(&mut tx, &[mem.id]).await?;
+    let updated = query::update(&mut tx, mem).await?;
+    let changes = Change::pairs(Action::Update, &before, std::slice::from_ref(&updated));
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Marks `mem` as deleted, recording an [`Action::Delete`] made by `witness` if it was active.
+pub(crate) async fn delete(conn: &mut PgConnection, witness: &Witness, mem: &Guest) -> Polite<()> {
+    let mut tx = conn.begin().await?;
+    let before = active(query::snapshot(&mut tx, &[mem.id]).await?);
+    query::delete(&mut tx, mem).await?;
+    let after = query::snapshot(&mut tx, &ids(&before)).await?;
+    let changes = Change::pairs(Action::Delete, &before, &after);
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Creates the guests in `mems`, recording an [`Action::Create`] made by `witness` for each.
+pub(crate) async fn create_many(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    mems: &[Guest],
+    retention: Duration,
+) -> Polite<Vec<Guest>> {
+    let mut tx = conn.begin().await?;
+    let keys = mems.iter().map(Guest::key).collect::<Vec<_>>();
+    free(&mut tx, witness, &keys, retention).await?;
+    let created = query::create_many(&mut tx, mems).await?;
+    let changes = Change::pairs(Action::Create, &[], &created);
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(created)
+}
+
+/// Updates the guests in `mems`, recording an [`Action::Update`] made by `witness` for each.
+pub(crate) async fn update_many(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    mems: &[Guest],
+) -> Polite<Vec<Guest>> {
+    let mut tx = conn.begin().await?;
+    let before = query::snapshot(&mut tx, &ids(mems)).await?;
+    let updated = query::update_many(&mut tx, mems).await?;
+    let changes = Change::pairs(Action::Update, &before, &updated);
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Marks the guests in `mems` as deleted, recording an [`Action::Delete`] made by `witness` for
+/// each that was active.
+pub(crate) async fn delete_many(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    mems: &[Guest],
+) -> Polite<()> {
+    let mut tx = conn.begin().await?;
+    let before = active(query::snapshot(&mut tx, &ids(mems)).await?);
+    query::delete_many(&mut tx, mems).await?;
+    let after = query::snapshot(&mut tx, &ids(&before)).await?;
+    let changes = Change::pairs(Action::Delete, &before, &after);
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Permanently removes the guests with `ids`, whether or not they are marked as deleted,
+/// recording an [`Action::Purge`] made by `witness` for each removed.
+pub(crate) async fn erase(conn: &mut PgConnection, witness: &Witness, ids: &[Uuid]) -> Polite<u64> {
+    let mut tx = conn.begin().await?;
+    let before = query::snapshot(&mut tx, ids).await?;
+    let erased = query::erase(&mut tx, ids).await?;
+    let changes = Change::pairs(Action::Purge, &before, &[]);
+    record(&mut tx, witness, &changes).await?;
+    tx.commit().await?;
+    Ok(erased)
+}
+
+/// Erases the checked out guests holding any of the name `keys` whose `retention` period has
+/// passed, so the names can be taken again, recording an [`Action::Purge`] made by `witness` for
+/// each.
+async fn free(
+    conn: &mut PgConnection,
+    witness: &Witness,
+    keys: &[String],
+    retention: Duration,
+) -> Polite<()> {
+    let expired = query::expired(&mut *conn, keys, retention).await?;
+    if expired.is_empty() {
+        return Ok(());
+    }
+    query::erase(&mut *conn, &ids(&expired)).await?;
+    let changes = Change::pairs(Action::Purge, &expired, &[]);
+    record(conn, witness, &changes).await
+}
+
+/// Keeps the guests in `guests` that have not checked out.
+fn active(mut guests: Vec<Guest>) -> Vec<Guest> {
+    guests.retain(|guest| guest.deleted_at.is_none());
+    guests
+}
+
+/// Collects the `id` of each guest in `guests`.
+fn ids(guests: &[Guest]) -> Vec<Uuid> {
+    guests.iter().map(|guest| guest.id).collect()
+}
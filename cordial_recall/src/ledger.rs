@@ -0,0 +1,222 @@
+//! The `ledger` module contains the [`Ledger`] struct, a handle to a database transaction that
+//! implements the [`Memorable`] trait for the type [`Guest`].  Changes written through a
+//! [`Ledger`] commit or roll back as a whole, along with the audit trail of those changes.
+use crate::audit::{self, Witness};
+use crate::query::{self, statement, timed};
+use cordial_guest::Guest;
+use cordial_memory::Memorable;
+use polite::{FauxPas, Polite};
+use sqlx::{Executor, PgConnection, Postgres, Transaction};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::trace;
+use uuid::Uuid;
+
+/// The `Entry` struct holds the transaction shared by a [`Ledger`] and its savepoints.
+pub(crate) struct Entry {
+    /// The open transaction, or `None` once the outermost [`Ledger`] has finished.
+    tx: Option<Transaction<'static, Postgres>>,
+    /// The number of savepoints opened, used to give each a unique name.
+    savepoints: usize,
+}
+
+impl Entry {
+    pub(crate) fn conn(&mut self) -> Polite<&mut PgConnection> {
+        match self.tx.as_mut() {
+            Some(tx) => Ok(&mut **tx),
+            None => Err(FauxPas::Closed(
+                "ledger already committed or rolled back".to_string(),
+            )),
+        }
+    }
+}
+
+/// The `Ledger` struct is a unit of work over a single database transaction, created with
+/// [`crate::Recall::ledger`].  Call [`Ledger::savepoint`] to open a nested `Ledger` that can be
+/// rolled back without abandoning the outer one.
+///
+/// The outermost `Ledger` rolls back if dropped without calling [`Ledger::commit`].  A savepoint
+/// dropped without being committed or rolled back leaves its changes in the outer `Ledger`.
+/// The `Ledger` is `Send` and `'static`, so it can be held across `.await` points in axum
+/// handlers and passed to functions generic over [`Memorable`].
+pub struct Ledger {
+    entry: Arc<Mutex<Entry>>,
+    /// The `retention` field holds the retention period for deleted guests, inherited from the
+    /// [`crate::Recall`] that opened the `Ledger`.
+    pub retention: Duration,
+    /// The `witness` field holds who is making changes, recorded in each [`cordial_guest::Audit`].
+    /// Inherited from the [`crate::Recall`] that opened the `Ledger`.
+    pub witness: Witness,
+    /// The name of the savepoint, or `None` for the outermost `Ledger`.
+    savepoint: Option<String>,
+}
+
+impl Ledger {
+    pub(crate) fn new(
+        tx: Transaction<'static, Postgres>,
+        retention: Duration,
+        witness: Witness,
+    ) -> Self {
+        let entry = Entry {
+            tx: Some(tx),
+            savepoints: 0,
+        };
+        Self {
+            entry: Arc::new(Mutex::new(entry)),
+            retention,
+            witness,
+            savepoint: None,
+        }
+    }
+
+    /// Locks the shared transaction for statements outside the [`Memorable`] operations.
+    pub(crate) async fn entry(&self) -> MutexGuard<'_, Entry> {
+        self.entry.lock().await
+    }
+
+    /// The `savepoint` method opens a nested `Ledger` on the same transaction.  Committing the
+    /// nested `Ledger` keeps its changes as part of the outer one, while rolling it back discards
+    /// only the changes made since the savepoint.
+    pub async fn savepoint(&self) -> Polite<Ledger> {
+        timed("savepoint", async {
+            let mut entry = self.entry.lock().await;
+            entry.savepoints += 1;
+            let name = format!("ledger_{}", entry.savepoints);
+            trace!("Opening savepoint {}.", &name);
+            entry
+                .conn()?
+                .execute(statement(&format!("SAVEPOINT {}", &name)))
+                .await?;
+            Ok(Self {
+                entry: self.entry.clone(),
+                retention: self.retention,
+                witness: self.witness.clone(),
+                savepoint: Some(name),
+            })
+        })
+        .await
+    }
+
+    /// The `commit` method commits the transaction, or releases the savepoint of a nested
+    /// `Ledger`.
+    pub async fn commit(self) -> Polite<()> {
+        timed("commit", async {
+            let mut entry = self.entry.lock().await;
+            match &self.savepoint {
+                Some(name) => {
+                    trace!("Releasing savepoint {}.", name);
+                    entry
+                        .conn()?
+                        .execute(statement(&format!("RELEASE SAVEPOINT {}", name)))
+                        .await?;
+                }
+                None => {
+                    trace!("Committing ledger.");
+                    entry.conn()?;
+                    if let Some(tx) = entry.tx.take() {
+                        statement("COMMIT");
+                        tx.commit().await?;
+                    }
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// The `rollback` method discards every change made through the transaction, or only the
+    /// changes made since the savepoint of a nested `Ledger`.
+    pub async fn rollback(self) -> Polite<()> {
+        timed("rollback", async {
+            let mut entry = self.entry.lock().await;
+            match &self.savepoint {
+                Some(name) => {
+                    trace!("Rolling back to savepoint {}.", name);
+                    let conn = entry.conn()?;
+                    conn.execute(statement(&format!("ROLLBACK TO SAVEPOINT {}", name)))
+                        .await?;
+                    conn.execute(statement(&format!("RELEASE SAVEPOINT {}", name)))
+                        .await?;
+                }
+                None => {
+                    trace!("Rolling back ledger.");
+                    entry.conn()?;
+                    if let Some(tx) = entry.tx.take() {
+                        statement("ROLLBACK");
+                        tx.rollback().await?;
+                    }
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl Memorable<Guest> for Ledger {
+    async fn get(&self, id: Uuid) -> Polite<Guest> {
+        timed("get", async {
+            let mut entry = self.entry.lock().await;
+            query::get(entry.conn()?, id).await
+        })
+        .await
+    }
+
+    async fn get_all(&self) -> Polite<Vec<Guest>> {
+        timed("get_all", async {
+            let mut entry = self.entry.lock().await;
+            query::get_all(entry.conn()?).await
+        })
+        .await
+    }
+
+    async fn create(&self, mem: &Guest) -> Polite<Guest> {
+        timed("create", async {
+            let mut entry = self.entry.lock().await;
+            audit::create(entry.conn()?, &self.witness, mem, self.retention).await
+        })
+        .await
+    }
+
+    async fn update(&self, mem: &Guest) -> Polite<Guest> {
+        timed("update", async {
+            let mut entry = self.entry.lock().await;
+            audit::update(entry.conn()?, &self.witness, mem).await
+        })
+        .await
+    }
+
+    async fn delete(&self, mem: &Guest) -> Polite<()> {
+        timed("delete", async {
+            let mut entry = self.entry.lock().await;
+            audit::delete(entry.conn()?, &self.witness, mem).await
+        })
+        .await
+    }
+
+    async fn create_many(&self, mems: &[Guest]) -> Polite<Vec<Guest>> {
+        timed("create_many", async {
+            let mut entry = self.entry.lock().await;
+            audit::create_many(entry.conn()?, &self.witness, mems, self.retention).await
+        })
+        .await
+    }
+
+    async fn update_many(&self, mems: &[Guest]) -> Polite<Vec<Guest>> {
+        timed("update_many", async {
+            let mut entry = self.entry.lock().await;
+            audit::update_many(entry.conn()?, &self.witness, mems).await
+        })
+        .await
+    }
+
+    async fn delete_many(&self, mems: &[Guest]) -> Polite<()> {
+        timed("delete_many", async {
+            let mut entry = self.entry.lock().await;
+            audit::delete_many(entry.conn()?, &self.witness, mems).await
+        })
+        .await
+    }
+}
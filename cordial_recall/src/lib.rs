@@ -1,3 +1,12 @@
+pub mod audit;
+pub mod ledger;
+mod query;
 pub mod recall;
+pub mod rehearsal;
+pub mod vitals;
 
+pub use crate::audit::Witness;
+pub use crate::ledger::Ledger;
 pub use crate::recall::Recall;
+pub use crate::rehearsal::{Rehearsal, Seeding};
+pub use cordial_guest::{Action, Audit, Migrations, Pool, Pulse, Review, Vitals};
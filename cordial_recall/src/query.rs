@@ -0,0 +1,331 @@
+//! The `query` module holds the statements behind the [`cordial_memory::Memorable`] operations
+//! for [`Guest`], written against a single [`PgConnection`] so that the pooled [`crate::Recall`]
+//! and the transactional [`crate::Ledger`] share them.  Operations spanning several statements
+//! open a nested transaction on the connection, which becomes a savepoint when the connection
+//! is already inside a transaction.  Each operation runs inside a span opened by [`timed`].
+use crate::recall::Recall;
+use chrono::{DateTime, Utc};
+use cordial_guest::Guest;
+use polite::Polite;
+use sqlx::{Connection, PgConnection};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use std::time::Instant;
+use tracing::{info_span, trace, Instrument, Span};
+use uuid::Uuid;
+
+/// Records `sql` as the `db.statement` of the current span, opened for each operation by
+/// [`timed`], and returns it.
+pub(crate) fn statement(sql: &str) -> &str {
+    Span::current().record("db.statement", sql.trim());
+    sql
+}
+
+/// Awaits `query` inside a `query` span carrying the OpenTelemetry database attributes, recording
+/// its duration under [`Recall::QUERY_SECONDS`] labelled with `operation` and whether it
+/// succeeded.  The statements of the operation fill in `db.statement`; see [`statement`].
+pub(crate) async fn timed<T>(
+    operation: &'static str,
+    query: impl Future<Output = Polite<T>>,
+) -> Polite<T> {
+    let span = info_span!(
+        "query",
+        otel.name = operation,
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = operation,
+        db.statement = tracing::field::Empty,
+    );
+    let started = Instant::now();
+    let result = query.instrument(span).await;
+    let outcome = match result {
+        Ok(_) => "ok",
+        Err(_) => "error",
+    };
+    metrics::histogram!(Recall::QUERY_SECONDS, "operation" => operation, "outcome" => outcome)
+        .record(started.elapsed().as_secs_f64());
+    result
+}
+
+pub(crate) async fn get(conn: &mut PgConnection, id: Uuid) -> Polite<Guest> {
+    trace!("Calling get() for id {}", &id);
+    Ok(sqlx::query_as::<_, Guest>(statement(
+        r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE id = $1 AND deleted_at IS NULL
+      "#,
+    ))
+    .bind(id)
+    .fetch_one(conn)
+    .await?)
+}
+
+pub(crate) async fn get_all(conn: &mut PgConnection) -> Polite<Vec<Guest>> {
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE deleted_at IS NULL
+      "#,
+    ))
+    .fetch_all(conn)
+    .await?;
+    Ok(req)
+}
+
+/// Returns the checked out guests holding any of the name `keys` whose `retention` period has
+/// passed, freeing the names once they are erased.
+pub(crate) async fn expired(
+    conn: &mut PgConnection,
+    keys: &[String],
+    retention: Duration,
+) -> Polite<Vec<Guest>> {
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE name_key = ANY($1) AND deleted_at < now() - make_interval(secs => $2)
+      "#,
+    ))
+    .bind(keys)
+    .bind(retention.as_secs_f64())
+    .fetch_all(conn)
+    .await?;
+    Ok(req)
+}
+
+pub(crate) async fn create(conn: &mut PgConnection, mem: &Guest) -> Polite<Guest> {
+    trace!("Calling create for {}.", &mem.name);
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      INSERT INTO guests (id, name, hash, name_key)
+      VALUES ($1, $2, $3, $4)
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+    ))
+    .bind(mem.id)
+    .bind(&mem.name)
+    .bind(&mem.hash)
+    .bind(mem.key())
+    .fetch_one(conn)
+    .await?;
+    Ok(req)
+}
+
+pub(crate) async fn update(conn: &mut PgConnection, mem: &Guest) -> Polite<Guest> {
+    trace!("Calling update for id {}", &mem.id);
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      UPDATE guests
+      SET name = $1, hash = $2, name_key = $3
+      WHERE id = $4 AND deleted_at IS NULL
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+    ))
+    .bind(&mem.name)
+    .bind(&mem.hash)
+    .bind(mem.key())
+    .bind(mem.id)
+    .fetch_one(conn)
+    .await?;
+    trace!("{:#?}", &req);
+    Ok(req)
+}
+
+pub(crate) async fn delete(conn: &mut PgConnection, mem: &Guest) -> Polite<()> {
+    trace!("Calling delete for id {}", &mem.id);
+    let req = sqlx::query::<_>(statement(
+        r#"
+      UPDATE guests
+      SET deleted_at = now()
+      WHERE id = $1 AND deleted_at IS NULL
+      "#,
+    ))
+    .bind(mem.id)
+    .execute(conn)
+    .await?;
+    trace!("{:#?}", &req);
+    Ok(())
+}
+
+pub(crate) async fn create_many(conn: &mut PgConnection, mems: &[Guest]) -> Polite<Vec<Guest>> {
+    trace!("Calling create_many for {} guests.", mems.len());
+    let (ids, names, hashes, keys) = columns(mems);
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      INSERT INTO guests (id, name, hash, name_key)
+      SELECT * FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[])
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+    ))
+    .bind(&ids)
+    .bind(&names)
+    .bind(&hashes)
+    .bind(&keys)
+    .fetch_all(conn)
+    .await?;
+    arrange(&ids, req)
+}
+
+pub(crate) async fn update_many(conn: &mut PgConnection, mems: &[Guest]) -> Polite<Vec<Guest>> {
+    trace!("Calling update_many for {} guests.", mems.len());
+    let (ids, names, hashes, keys) = columns(mems);
+    let mut tx = conn.begin().await?;
+    let req = sqlx::query_as::<_, Guest>(statement(
+        r#"
+      UPDATE guests
+      SET name = batch.name, hash = batch.hash, name_key = batch.name_key
+      FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[])
+        AS batch (id, name, hash, name_key)
+      WHERE guests.id = batch.id AND guests.deleted_at IS NULL
+      RETURNING guests.id, guests.name, guests.hash, guests.created_at, guests.updated_at,
+        guests.last_login_at, guests.deleted_at
+      "#,
+    ))
+    .bind(&ids)
+    .bind(&names)
+    .bind(&hashes)
+    .bind(&keys)
+    .fetch_all(&mut *tx)
+    .await?;
+    let req = arrange(&ids, req)?;
+    tx.commit().await?;
+    Ok(req)
+}
+
+pub(crate) async fn delete_many(conn: &mut PgConnection, mems: &[Guest]) -> Polite<()> {
+    trace!("Calling delete_many for {} guests.", mems.len());
+    let (ids, _, _, _) = columns(mems);
+    let req = sqlx::query::<_>(statement(
+        r#"
+      UPDATE guests
+      SET deleted_at = now()
+      WHERE id = ANY($1) AND deleted_at IS NULL
+      "#,
+    ))
+    .bind(&ids)
+    .execute(conn)
+    .await?;
+    trace!("{:#?}", &req);
+    Ok(())
+}
+
+/// Returns the guests with `ids`, deleted or not, locking their rows until the end of the
+/// current transaction so their state can be compared after a change.
+pub(crate) async fn snapshot(conn: &mut PgConnection, ids: &[Uuid]) -> Polite<Vec<Guest>> {
+    trace!("Calling snapshot for {} guests.", ids.len());
+    let req = sqlx::query_as::<_, Guest>(
+        r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE id = ANY($1)
+      FOR UPDATE
+      "#,
+    )
+    .bind(ids)
+    .fetch_all(conn)
+    .await?;
+    Ok(req)
+}
+
+/// Takes a lock on `seed` held until the end of the current transaction, so that concurrent
+/// seeding with the same seed runs one at a time.
+pub(crate) async fn lock(conn: &mut PgConnection, seed: i64) -> Polite<()> {
+    trace!("Locking seed {}", seed);
+    sqlx::query::<_>(statement("SELECT pg_advisory_xact_lock($1)"))
+        .bind(seed)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+pub(crate) async fn marker(conn: &mut PgConnection, seed: i64) -> Polite<Option<Marker>> {
+    trace!("Calling marker for seed {}", seed);
+    let req = sqlx::query_as::<_, Marker>(statement(
+        r#"
+      SELECT seed, count, pass, planted_at
+      FROM seeds
+      WHERE seed = $1
+      "#,
+    ))
+    .bind(seed)
+    .fetch_optional(conn)
+    .await?;
+    Ok(req)
+}
+
+pub(crate) async fn mark(
+    conn: &mut PgConnection,
+    seed: i64,
+    count: i32,
+    pass: &str,
+) -> Polite<Marker> {
+    trace!("Calling mark for seed {}", seed);
+    let req = sqlx::query_as::<_, Marker>(statement(
+        r#"
+      INSERT INTO seeds (seed, count, pass)
+      VALUES ($1, $2, $3)
+      RETURNING seed, count, pass, planted_at
+      "#,
+    ))
+    .bind(seed)
+    .bind(count)
+    .bind(pass)
+    .fetch_one(conn)
+    .await?;
+    Ok(req)
+}
+
+pub(crate) async fn unmark(conn: &mut PgConnection, seed: i64) -> Polite<()> {
+    trace!("Calling unmark for seed {}", seed);
+    sqlx::query::<_>(statement("DELETE FROM seeds WHERE seed = $1"))
+        .bind(seed)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Permanently removes the guests with `ids`, whether or not they are marked as deleted.
+pub(crate) async fn erase(conn: &mut PgConnection, ids: &[Uuid]) -> Polite<u64> {
+    trace!("Calling erase for {} guests.", ids.len());
+    let req = sqlx::query::<_>(statement("DELETE FROM guests WHERE id = ANY($1)"))
+        .bind(ids)
+        .execute(conn)
+        .await?;
+    Ok(req.rows_affected())
+}
+
+/// A row of the `seeds` table, recording the settings a seed was planted with.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct Marker {
+    pub(crate) seed: i64,
+    pub(crate) count: i32,
+    pub(crate) pass: String,
+    pub(crate) planted_at: DateTime<Utc>,
+}
+
+/// The column arrays bound by the batch queries: ids, names, hashes and name keys.
+type Columns = (Vec<Uuid>, Vec<String>, Vec<String>, Vec<String>);
+
+/// Splits `mems` into the column arrays bound by the batch queries.
+fn columns(mems: &[Guest]) -> Columns {
+    let ids = mems.iter().map(|mem| mem.id).collect();
+    let names = mems.iter().map(|mem| mem.name.clone()).collect();
+    let hashes = mems.iter().map(|mem| mem.hash.clone()).collect();
+    let keys = mems.iter().map(|mem| mem.key()).collect();
+    (ids, names, hashes, keys)
+}
+
+/// Orders the rows returned by a batch query to match `ids`.  Commits a [`polite::FauxPas`] if
+/// any `id` is missing from `rows`.
+fn arrange(ids: &[Uuid], rows: Vec<Guest>) -> Polite<Vec<Guest>> {
+    let mut rows = rows
+        .into_iter()
+        .map(|row| (row.id, row))
+        .collect::<HashMap<Uuid, Guest>>();
+    ids.iter()
+        .map(|id| rows.remove(id).ok_or(sqlx::Error::RowNotFound.into()))
+        .collect()
+}
@@ -1,12 +1,18 @@
 //! The `recall` crate contains the [`Recall`] struct, with methods for
 //! constructing a handle for accessing a Postgres database connection pool.  The [`Recall`] struct
 //! implements the [`Memorable`] trait for the type [`Guest`], to enable CRUD access to the
-//! database for managing [`Guest`] data.
-use cordial_guest::Guest;
-use cordial_memory::Memorable;
+//! database for managing [`Guest`] data.  Every change made through a [`Recall`] is written to
+//! the audit trail in the same transaction; see [`Audit`].
+use crate::audit::{self, Change, Witness};
+use crate::ledger::Ledger;
+use crate::query::{self, statement, timed};
+use crate::vitals;
+use cordial_guest::{Action, Audit, Guest, Inquiry, Review, Vitals};
+use cordial_memory::{Memorable, Roster};
 use cordial_posture::Posture;
 use polite::Polite;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::time::Duration;
 use tracing::trace;
 use uuid::Uuid;
 
@@ -15,106 +21,499 @@ use uuid::Uuid;
 pub struct Recall {
     /// The `book` field holds a handle to a pool of database connections.
     pub book: PgPool,
+    /// The `retention` field holds the period a deleted [`Guest`] keeps its name reserved before
+    /// the entry becomes eligible for purging.
+    pub retention: Duration,
+    /// The `witness` field holds who is making changes, recorded in each [`Audit`].  Defaults to
+    /// [`Witness::SYSTEM`].
+    pub witness: Witness,
 }
 
 impl Recall {
-    /// Creates a new `Recall` using `book`, a handle to the database.
+    /// The default `retention` period of thirty days.
+    pub const RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+    /// The name of the histogram timing each database operation of a `Recall` or [`Ledger`] in
+    /// seconds, from acquiring a connection until the query returns.  Labelled with the
+    /// `operation`, such as `get` or `create_many`, and the `outcome`, either `ok` or `error`.
+    pub const QUERY_SECONDS: &'static str = "cordial_recall_query_duration_seconds";
+
+    /// Creates a new `Recall` using `book`, a handle to the database, and the default
+    /// [`Recall::RETENTION`] period.
     pub fn new(book: PgPool) -> Self {
-        Self { book }
+        Self {
+            book,
+            retention: Self::RETENTION,
+            witness: Witness::default(),
+        }
+    }
+
+    /// The `attended` method returns a copy of the `Recall` sharing the same connection pool,
+    /// recording `witness` in the audit trail of the changes it makes.
+    pub fn attended(&self, witness: Witness) -> Self {
+        Self {
+            witness,
+            ..self.clone()
+        }
+    }
+
+    /// The `audits` method returns the [`Audit`] entries matching the filters in `review`,
+    /// newest first.  Commits a [`polite::FauxPas::Invalid`] if `review` fails
+    /// [`Review::validate`].
+    pub async fn audits(&self, review: &Review) -> Polite<Vec<Audit>> {
+        trace!("Calling audits for {:?}", review);
+        timed("audits", async {
+            review.validate()?;
+            let mut query = QueryBuilder::<Postgres>::new(
+                r#"
+      SELECT id, recorded_at, actor, guest_id, action, diff, request_id, ip
+      FROM audits
+      WHERE TRUE
+      "#,
+            );
+            if let Some(guest_id) = review.guest_id {
+                query.push(" AND guest_id = ").push_bind(guest_id);
+            }
+            if let Some(actor) = &review.actor {
+                query.push(" AND actor = ").push_bind(actor.clone());
+            }
+            if let Some(action) = review.action {
+                query.push(" AND action = ").push_bind(action);
+            }
+            if let Some(request_id) = &review.request_id {
+                query
+                    .push(" AND request_id = ")
+                    .push_bind(request_id.clone());
+            }
+            if let Some(since) = review.since {
+                query.push(" AND recorded_at >= ").push_bind(since);
+            }
+            if let Some(until) = review.until {
+                query.push(" AND recorded_at <= ").push_bind(until);
+            }
+            if let Some(cursor) = review.cursor {
+                query.push(" AND id < ").push_bind(cursor);
+            }
+            query
+                .push(" ORDER BY id DESC LIMIT ")
+                .push_bind(review.limit.unwrap_or(Review::LIMIT));
+            statement(query.sql());
+            let req = query
+                .build_query_as::<Audit>()
+                .fetch_all(&self.book)
+                .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `ledger` method begins a database transaction, returning a [`Ledger`] that offers the
+    /// same [`Memorable`] operations as the `Recall`, recording changes as the `witness` of the
+    /// `Recall`.  Nothing written through the [`Ledger`], audits included, is visible to other
+    /// connections until [`Ledger::commit`] is called.
+    pub async fn ledger(&self) -> Polite<Ledger> {
+        trace!("Opening ledger.");
+        timed("ledger", async {
+            statement("BEGIN");
+            let tx = self.book.begin().await?;
+            Ok(Ledger::new(tx, self.retention, self.witness.clone()))
+        })
+        .await
+    }
+
+    /// The `vitals` method reports on the health of the database, allowing it `timeout` to
+    /// answer.  See [`vitals::take`].
+    pub async fn vitals(&self, timeout: Duration) -> Vitals {
+        vitals::take(&self.book, timeout).await
+    }
+
+    /// The `inquire` method returns the [`Guest`] entries matching the filters in `inquiry`,
+    /// ordered by the field and direction it specifies.
+    pub async fn inquire(&self, inquiry: &Inquiry) -> Polite<Vec<Guest>> {
+        trace!("Calling inquire for {:?}", inquiry);
+        timed("inquire", async {
+            let mut query = QueryBuilder::<Postgres>::new(
+                r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE deleted_at IS NULL
+      "#,
+            );
+            let bounds = [
+                ("created_at >= ", inquiry.created_after),
+                ("created_at <= ", inquiry.created_before),
+                ("updated_at >= ", inquiry.updated_after),
+                ("updated_at <= ", inquiry.updated_before),
+                ("last_login_at >= ", inquiry.login_after),
+                ("last_login_at <= ", inquiry.login_before),
+            ];
+            for (clause, bound) in bounds {
+                if let Some(time) = bound {
+                    query.push(" AND ").push(clause).push_bind(time);
+                }
+            }
+            let sort = inquiry.sort.unwrap_or_default();
+            let order = inquiry.order.unwrap_or_default();
+            query
+                .push(" ORDER BY ")
+                .push(sort.column())
+                .push(" ")
+                .push(order.keyword())
+                .push(", id");
+            statement(query.sql());
+            let req = query
+                .build_query_as::<Guest>()
+                .fetch_all(&self.book)
+                .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `get_by_name` method returns the active [`Guest`] whose name matches `name`, ignoring
+    /// case and Unicode compatibility differences.  See [`Guest::fold`].
+    pub async fn get_by_name(&self, name: &str) -> Polite<Guest> {
+        trace!("Calling get_by_name for {}", name);
+        timed("get_by_name", async {
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE name_key = $1 AND deleted_at IS NULL
+      "#,
+            ))
+            .bind(Guest::fold(name))
+            .fetch_one(&self.book)
+            .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `find_by_name` method returns the active [`Guest`] whose name matches `name` as in
+    /// [`Recall::get_by_name`], or `None` if there is no such guest.
+    pub async fn find_by_name(&self, name: &str) -> Polite<Option<Guest>> {
+        trace!("Calling find_by_name for {}", name);
+        timed("find_by_name", async {
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE name_key = $1 AND deleted_at IS NULL
+      "#,
+            ))
+            .bind(Guest::fold(name))
+            .fetch_optional(&self.book)
+            .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `exists_by_name` method returns `true` if `name` is taken, ignoring case and Unicode
+    /// compatibility differences.  The names of deleted guests remain taken until their
+    /// `retention` period passes.
+    pub async fn exists_by_name(&self, name: &str) -> Polite<bool> {
+        trace!("Calling exists_by_name for {}", name);
+        timed("exists_by_name", async {
+            let req = sqlx::query_scalar::<_, bool>(statement(
+                r#"
+      SELECT EXISTS (
+        SELECT 1
+        FROM guests
+        WHERE name_key = $1
+          AND (deleted_at IS NULL OR deleted_at >= now() - make_interval(secs => $2))
+      )
+      "#,
+            ))
+            .bind(Guest::fold(name))
+            .bind(self.retention.as_secs_f64())
+            .fetch_one(&self.book)
+            .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `deleted` method returns the [`Guest`] entries marked as deleted and not yet purged,
+    /// most recently deleted first.
+    pub async fn deleted(&self) -> Polite<Vec<Guest>> {
+        trace!("Calling deleted.");
+        timed("deleted", async {
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      SELECT id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      FROM guests
+      WHERE deleted_at IS NOT NULL
+      ORDER BY deleted_at DESC, id
+      "#,
+            ))
+            .fetch_all(&self.book)
+            .await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `restore` method clears the deletion mark of the [`Guest`] with `id`, returning the
+    /// restored entry.  Commits a [`polite::FauxPas`] if no deleted guest has the given `id`.
+    pub async fn restore(&self, id: Uuid) -> Polite<Guest> {
+        trace!("Calling restore for id {}", &id);
+        timed("restore", async {
+            let mut tx = self.book.begin().await?;
+            let before = query::snapshot(&mut tx, &[id]).await?;
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      UPDATE guests
+      SET deleted_at = NULL
+      WHERE id = $1 AND deleted_at IS NOT NULL
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+            ))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            let changes = Change::pairs(Action::Restore, &before, std::slice::from_ref(&req));
+            audit::record(&mut tx, &self.witness, &changes).await?;
+            tx.commit().await?;
+            Ok(req)
+        })
+        .await
+    }
+
+    /// The `purge` method permanently removes the deleted [`Guest`] with `id`.  Commits a
+    /// [`polite::FauxPas`] if no deleted guest has the given `id`, so that active guests cannot be
+    /// purged by mistake.
+    pub async fn purge(&self, id: Uuid) -> Polite<()> {
+        trace!("Calling purge for id {}", &id);
+        timed("purge", async {
+            let mut tx = self.book.begin().await?;
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      DELETE FROM guests
+      WHERE id = $1 AND deleted_at IS NOT NULL
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+            ))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            trace!("{:#?}", &req);
+            let changes = Change::pairs(Action::Purge, std::slice::from_ref(&req), &[]);
+            audit::record(&mut tx, &self.witness, &changes).await?;
+            tx.commit().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// The `sweep` method permanently removes every deleted [`Guest`] whose `retention` period
+    /// has passed, freeing their names, and returns the number of entries removed.
+    pub async fn sweep(&self) -> Polite<u64> {
+        trace!("Calling sweep.");
+        timed("sweep", async {
+            let mut tx = self.book.begin().await?;
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      DELETE FROM guests
+      WHERE deleted_at < now() - make_interval(secs => $1)
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+            ))
+            .bind(self.retention.as_secs_f64())
+            .fetch_all(&mut *tx)
+            .await?;
+            trace!("Swept {} guests.", req.len());
+            let changes = Change::pairs(Action::Purge, &req, &[]);
+            audit::record(&mut tx, &self.witness, &changes).await?;
+            tx.commit().await?;
+            Ok(req.len() as u64)
+        })
+        .await
+    }
+
+    /// The `record_login` method stamps the `last_login_at` field of the [`Guest`] with `id` to
+    /// the current time, returning the updated entry.  Commits a [`polite::FauxPas`] if no
+    /// active guest has the given `id`, recording the refused attempt in the audit trail.
+    pub async fn record_login(&self, id: Uuid) -> Polite<Guest> {
+        trace!("Calling record_login for id {}", &id);
+        timed("record_login", async {
+            let mut tx = self.book.begin().await?;
+            let before = query::snapshot(&mut tx, &[id]).await?;
+            let req = sqlx::query_as::<_, Guest>(statement(
+                r#"
+      UPDATE guests
+      SET last_login_at = now()
+      WHERE id = $1 AND deleted_at IS NULL
+      RETURNING id, name, hash, created_at, updated_at, last_login_at, deleted_at
+      "#,
+            ))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await;
+            match req {
+                Ok(req) => {
+                    let changes = Change::pairs(Action::Login, &before, std::slice::from_ref(&req));
+                    audit::record(&mut tx, &self.witness, &changes).await?;
+                    tx.commit().await?;
+                    Ok(req)
+                }
+                Err(e) => {
+                    tx.rollback().await?;
+                    self.refuse_login(id).await?;
+                    Err(e.into())
+                }
+            }
+        })
+        .await
+    }
+
+    /// The `refuse_login` method records a refused login for the [`Guest`] with `id` in the audit
+    /// trail, without changing the guest.
+    pub async fn refuse_login(&self, id: Uuid) -> Polite<()> {
+        trace!("Calling refuse_login for id {}", &id);
+        timed("refuse_login", async {
+            let mut conn = self.book.acquire().await?;
+            let change = Change {
+                action: Action::LoginFailed,
+                guest_id: id,
+                before: None,
+                after: None,
+            };
+            audit::record(&mut conn, &self.witness, &[change]).await
+        })
+        .await
     }
 }
 
 impl From<Posture> for Recall {
     fn from(posture: Posture) -> Self {
-        let book = posture.book();
-        Recall::new(book)
+        Recall::from(&posture)
     }
 }
 
 impl From<&Posture> for Recall {
     fn from(posture: &Posture) -> Self {
         let book = posture.book();
-        Recall::new(book)
+        let mut recall = Recall::new(book);
+        recall.retention = posture.retention();
+        recall
     }
 }
 
 #[async_trait::async_trait]
 impl Memorable<Guest> for Recall {
     async fn get(&self, id: Uuid) -> Polite<Guest> {
-        trace!("Calling get() for id {}", &id);
-        Ok(sqlx::query_as::<_, Guest>(
-            r#"
-      SELECT id, name, hash
-      FROM guests
-      WHERE id = $1
-      "#,
-            // RETURNING (id, username, hash)
-        )
-        .bind(id)
-        .fetch_one(&self.book)
-        .await?)
+        timed("get", async {
+            let mut conn = self.book.acquire().await?;
+            query::get(&mut conn, id).await
+        })
+        .await
     }
 
     async fn get_all(&self) -> Polite<Vec<Guest>> {
-        let req = sqlx::query_as::<_, Guest>(
-            r#"
-      SELECT id, name, hash
-      FROM guests
-      "#,
-        )
-        .fetch_all(&self.book)
-        .await?;
-        Ok(req)
+        timed("get_all", async {
+            let mut conn = self.book.acquire().await?;
+            query::get_all(&mut conn).await
+        })
+        .await
     }
 
     async fn create(&self, mem: &Guest) -> Polite<Guest> {
-        trace!("Calling create for {}.", &mem.name);
-        let req = sqlx::query_as::<_, Guest>(
-            r#"
-      INSERT INTO guests (id, name, hash)
-      VALUES ($1, $2, $3)
-      RETURNING id, name, hash
-      "#,
-        )
-        .bind(mem.id)
-        .bind(&mem.name)
-        .bind(&mem.hash)
-        .fetch_one(&self.book)
-        .await?;
-        Ok(req)
+        timed("create", async {
+            let mut conn = self.book.acquire().await?;
+            audit::create(&mut conn, &self.witness, mem, self.retention).await
+        })
+        .await
     }
 
     async fn update(&self, mem: &Guest) -> Polite<Guest> {
-        trace!("Calling update for id {}", &mem.id);
-        let req = sqlx::query(
-            r#"
-      UPDATE guests
-      SET name = $1, hash = $2
-      WHERE id = $3
-      "#,
-        )
-        .bind(&mem.name)
-        .bind(&mem.hash)
-        .bind(mem.id)
-        .execute(&self.book)
-        .await?;
-        trace!("{:#?}", &req);
-
-        Ok(mem.clone())
+        timed("update", async {
+            let mut conn = self.book.acquire().await?;
+            audit::update(&mut conn, &self.witness, mem).await
+        })
+        .await
     }
 
+    /// Marks the [`Guest`] as deleted.  The entry remains in the database, reserving its name,
+    /// until it is restored or purged with [`Recall::restore`] and [`Recall::purge`].
     async fn delete(&self, mem: &Guest) -> Polite<()> {
-        trace!("Calling delete for id {}", &mem.id);
-        let req = sqlx::query::<_>(
-            r#"
-      DELETE from guests
-      WHERE id = $1
-      "#,
+        timed("delete", async {
+            let mut conn = self.book.acquire().await?;
+            audit::delete(&mut conn, &self.witness, mem).await
+        })
+        .await
+    }
+
+    /// Creates the guests in `mems` with a single multi-row insert inside one transaction.  The
+    /// columns are bound as arrays and expanded with `UNNEST`, so the statement size does not grow
+    /// with the batch.
+    async fn create_many(&self, mems: &[Guest]) -> Polite<Vec<Guest>> {
+        timed("create_many", async {
+            let mut conn = self.book.acquire().await?;
+            audit::create_many(&mut conn, &self.witness, mems, self.retention).await
+        })
+        .await
+    }
+
+    /// Updates the guests in `mems` with a single statement inside one transaction.  Rolls back
+    /// and commits a [`polite::FauxPas`] if any `id` does not match an active guest.
+    async fn update_many(&self, mems: &[Guest]) -> Polite<Vec<Guest>> {
+        timed("update_many", async {
+            let mut conn = self.book.acquire().await?;
+            audit::update_many(&mut conn, &self.witness, mems).await
+        })
+        .await
+    }
+
+    /// Marks the guests in `mems` as deleted with a single statement.
+    async fn delete_many(&self, mems: &[Guest]) -> Polite<()> {
+        timed("delete_many", async {
+            let mut conn = self.book.acquire().await?;
+            audit::delete_many(&mut conn, &self.witness, mems).await
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl Roster for Recall {
+    async fn taken(&self, name: &str) -> Polite<bool> {
+        self.exists_by_name(name).await
+    }
+
+    /// Checks every name in `names` with a single query.  Names that fold to the same key as an
+    /// earlier entry of `names` are left out, since only one of them could be registered.
+    async fn vacant(&self, names: &[String]) -> Polite<Vec<String>> {
+        trace!("Calling vacant for {} names.", names.len());
+        timed("vacant", async {
+            let keys = names
+                .iter()
+                .map(|name| Guest::fold(name))
+                .collect::<Vec<String>>();
+            let req = sqlx::query_scalar::<_, String>(statement(
+                r#"
+      SELECT name
+      FROM (
+        SELECT DISTINCT ON (candidate.key) candidate.name, candidate.rank
+        FROM UNNEST($1::text[], $2::text[]) WITH ORDINALITY AS candidate (name, key, rank)
+        WHERE NOT EXISTS (
+          SELECT 1
+          FROM guests
+          WHERE name_key = candidate.key
+            AND (deleted_at IS NULL OR deleted_at >= now() - make_interval(secs => $3))
         )
-        .bind(mem.id)
-        .execute(&self.book)
-        .await?;
-        trace!("{:#?}", &req);
-        Ok(())
+        ORDER BY candidate.key, candidate.rank
+      ) AS vacant
+      ORDER BY rank
+      "#,
+            ))
+            .bind(names)
+            .bind(&keys)
+            .bind(self.retention.as_secs_f64())
+            .fetch_all(&self.book)
+            .await?;
+            Ok(req)
+        })
+        .await
     }
 }
@@ -0,0 +1,215 @@
+//! The `rehearsal` module contains the [`Rehearsal`] struct, which seeds the book with
+//! reproducible [`Guest`] fixtures improvised by a seeded [`Improv`].  Each seed planted is
+//! recorded in the `seeds` table, so seeding twice with the same settings changes nothing.
+use crate::audit;
+use crate::query::{self, timed};
+use crate::recall::Recall;
+use chrono::{DateTime, Utc};
+use cordial_guest::Guest;
+use cordial_improv::{Improv, Pass};
+use cordial_memory::Memorable;
+use polite::{FauxPas, Flaw, Polite};
+use serde::{Deserialize, Serialize};
+use tracing::{info, trace};
+
+/// The `Rehearsal` struct holds the settings for seeding the book: the number of guests, the seed
+/// for the [`Improv`] producing them, the [`Pass`] configuration of their passwords, and the
+/// number of guests created per batch.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rehearsal {
+    /// The `seed` field seeds the [`Improv`], so that the same seed produces the same guests.
+    pub seed: u64,
+    /// The `count` field holds the number of guests to create.
+    pub count: usize,
+    /// The `pass` field configures the passwords of the guests.
+    pub pass: Pass,
+    /// The `batch` field holds the number of guests created in each call to
+    /// [`Memorable::create_many`].
+    pub batch: usize,
+}
+
+impl Rehearsal {
+    /// The default number of guests created per batch.
+    pub const BATCH: usize = 500;
+
+    /// Creates a new `Rehearsal` producing `count` guests from `seed`, with the default [`Pass`]
+    /// configuration and [`Rehearsal::BATCH`] size.  Modify the fields directly after construction
+    /// to customize.
+    pub fn new(seed: u64, count: usize) -> Self {
+        Self {
+            seed,
+            count,
+            ..Default::default()
+        }
+    }
+
+    /// The `fixtures` method returns the guests produced by the `Rehearsal`, which are the same
+    /// on every call.  Commits a [`FauxPas`] if the `pass` configuration fails
+    /// [`Pass::validate`] or the `count` does not fit the `seeds` table.
+    pub fn fixtures(&self) -> Polite<Vec<Guest>> {
+        self.pass.validate()?;
+        self.tally()?;
+        let mut improv = Improv::seeded(self.seed);
+        improv.pass = self.pass.into();
+        improv.guests(self.count)
+    }
+
+    /// The `stage` method creates the [`Rehearsal::fixtures`] in `memory`, `batch` guests at a
+    /// time, and returns the created entries.  Does not consult the `seeds` table, so it can
+    /// populate any [`Memorable`] store.
+    pub async fn stage<M: Memorable<Guest> + ?Sized>(&self, memory: &M) -> Polite<Vec<Guest>> {
+        let fixtures = self.fixtures()?;
+        let mut staged = Vec::with_capacity(fixtures.len());
+        for batch in fixtures.chunks(self.batch.max(1)) {
+            trace!("Staging {} guests.", batch.len());
+            staged.extend(memory.create_many(batch).await?);
+        }
+        Ok(staged)
+    }
+
+    /// The `seed` method plants the fixtures in the book of `recall` within one transaction,
+    /// unless the `seed` has already been planted.  Commits a [`FauxPas::Invalid`] if the `seed`
+    /// was planted with a different `count` or `pass`; call [`Rehearsal::reset`] to replant it.
+    pub async fn seed(&self, recall: &Recall) -> Polite<Seeding> {
+        let ledger = recall.ledger().await?;
+        let marker = {
+            let mut entry = ledger.entry().await;
+            timed("lock", query::lock(entry.conn()?, self.key())).await?;
+            timed("marker", query::marker(entry.conn()?, self.key())).await?
+        };
+        if let Some(marker) = marker {
+            ledger.rollback().await?;
+            return match self.matches(&marker)? {
+                true => {
+                    info!("Seed {} already planted.", self.seed);
+                    Ok(Seeding::new(&marker, 0))
+                }
+                false => Err(FauxPas::Invalid(vec![Flaw::new(
+                    "seed",
+                    "conflict",
+                    &format!(
+                        "Seed {} was planted with different settings.  Reset to replant it.",
+                        self.seed
+                    ),
+                )])),
+            };
+        }
+        let planted = self.stage(&ledger).await?;
+        let marker = {
+            let mut entry = ledger.entry().await;
+            timed(
+                "mark",
+                query::mark(entry.conn()?, self.key(), self.tally()?, &self.pass()?),
+            )
+            .await?
+        };
+        ledger.commit().await?;
+        info!("Planted {} guests from seed {}.", planted.len(), self.seed);
+        Ok(Seeding::new(&marker, planted.len()))
+    }
+
+    /// The `reset` method returns the book of `recall` to its seeded state within one
+    /// transaction.  Permanently removes the guests planted by any earlier use of the `seed`,
+    /// including their later changes, then plants the fixtures again.  Guests not created by the
+    /// seed are left alone.
+    pub async fn reset(&self, recall: &Recall) -> Polite<Seeding> {
+        let ledger = recall.ledger().await?;
+        {
+            let mut entry = ledger.entry().await;
+            timed("lock", query::lock(entry.conn()?, self.key())).await?;
+            if let Some(marker) = timed("marker", query::marker(entry.conn()?, self.key())).await? {
+                let planted = Rehearsal {
+                    count: usize::try_from(marker.count).unwrap_or_default(),
+                    pass: serde_json::from_str(&marker.pass)?,
+                    ..*self
+                };
+                let ids = planted
+                    .fixtures()?
+                    .iter()
+                    .map(|guest| guest.id)
+                    .collect::<Vec<_>>();
+                let erased =
+                    timed("erase", audit::erase(entry.conn()?, &ledger.witness, &ids)).await?;
+                trace!("Erased {} guests from seed {}.", erased, self.seed);
+                timed("unmark", query::unmark(entry.conn()?, self.key())).await?;
+            }
+        }
+        let planted = self.stage(&ledger).await?;
+        let marker = {
+            let mut entry = ledger.entry().await;
+            timed(
+                "mark",
+                query::mark(entry.conn()?, self.key(), self.tally()?, &self.pass()?),
+            )
+            .await?
+        };
+        ledger.commit().await?;
+        info!(
+            "Replanted {} guests from seed {}.",
+            planted.len(),
+            self.seed
+        );
+        Ok(Seeding::new(&marker, planted.len()))
+    }
+
+    /// The `seed` as stored in the `seeds` table, reinterpreting the bits as a signed integer.
+    fn key(&self) -> i64 {
+        self.seed as i64
+    }
+
+    /// The `count` as stored in the `seeds` table.
+    fn tally(&self) -> Polite<i32> {
+        i32::try_from(self.count).map_err(|_| {
+            FauxPas::Invalid(vec![Flaw::new(
+                "count",
+                "range",
+                &format!("Must be at most {}.", i32::MAX),
+            )])
+        })
+    }
+
+    /// The `pass` configuration as stored in the `seeds` table.
+    fn pass(&self) -> Polite<String> {
+        Ok(serde_json::to_string(&self.pass)?)
+    }
+
+    fn matches(&self, marker: &query::Marker) -> Polite<bool> {
+        Ok(marker.count == self.tally()? && marker.pass == self.pass()?)
+    }
+}
+
+impl Default for Rehearsal {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            count: 100,
+            pass: Pass::default(),
+            batch: Self::BATCH,
+        }
+    }
+}
+
+/// The `Seeding` struct reports the outcome of [`Rehearsal::seed`] or [`Rehearsal::reset`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Seeding {
+    /// The seed planted.
+    pub seed: u64,
+    /// The number of guests the seed produces.
+    pub count: usize,
+    /// When the seed was first planted.
+    pub planted_at: DateTime<Utc>,
+    /// The number of guests created by this call, zero if the seed was already planted.
+    pub planted: usize,
+}
+
+impl Seeding {
+    fn new(marker: &query::Marker, planted: usize) -> Self {
+        Self {
+            seed: marker.seed as u64,
+            count: usize::try_from(marker.count).unwrap_or_default(),
+            planted_at: marker.planted_at,
+            planted,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! The `vitals` module takes the [`Vitals`] of the database behind a [`crate::Recall`], a report
+//! on its health used to decide whether the server is ready for traffic.
+use crate::query::{statement, timed};
+use cordial_guest::{Migrations, Milestone, Pool, Pulse, Vitals};
+use cordial_posture::Posture;
+use polite::Polite;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use tracing::{trace, warn};
+
+/// The `take` function checks the database behind `book`, allowing it `timeout` to hand out a
+/// connection, report its version and list its applied migrations.  Failures are recorded in
+/// the report rather than returned.
+pub async fn take(book: &PgPool, timeout: Duration) -> Vitals {
+    let pool = pool(book);
+    let started = Instant::now();
+    let checked = tokio::time::timeout(timeout, timed("vitals", probe(book))).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let (version, milestones, error) = match checked {
+        Ok(Ok((version, milestones))) => (Some(version), Some(milestones), None),
+        Ok(Err(e)) => (None, None, Some(e.to_string())),
+        Err(_) => (
+            None,
+            None,
+            Some(format!("No answer within {} ms.", timeout.as_millis())),
+        ),
+    };
+    if let Some(e) = &error {
+        warn!("Database check failed: {}", e);
+    }
+    let database = Pulse {
+        reachable: error.is_none(),
+        latency_ms,
+        version,
+        error,
+    };
+    let migrations = Migrations::from(milestones);
+    Vitals {
+        ready: database.reachable && migrations.current,
+        database,
+        migrations,
+        pool,
+    }
+}
+
+async fn probe(book: &PgPool) -> Polite<(String, Vec<Milestone>)> {
+    trace!("Probing database.");
+    let mut connection = book.acquire().await?;
+    let version = sqlx::query_scalar(statement("SELECT version()"))
+        .fetch_one(&mut *connection)
+        .await?;
+    let milestones = Posture::survey(&mut connection).await?;
+    Ok((version, milestones))
+}
+
+/// The `pool` function measures the connection pool `book`.
+pub fn pool(book: &PgPool) -> Pool {
+    let size = book.size();
+    let idle = book.num_idle();
+    let busy = size.saturating_sub(u32::try_from(idle).unwrap_or(u32::MAX));
+    let max = book.options().get_max_connections();
+    let saturation = match max {
+        0 => 0.0,
+        max => f64::from(busy) / f64::from(max),
+    };
+    Pool {
+        size,
+        idle,
+        busy,
+        max,
+        saturation,
+    }
+}
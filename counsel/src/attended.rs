@@ -0,0 +1,58 @@
+//! The `attended` module contains the [`Attended`] extractor, which attaches the caller of a
+//! request to the [`Recall`] handling it, so that the changes it makes are attributed in the
+//! audit trail.
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::HeaderName;
+use cordial_recall::{Recall, Witness};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+/// The `Attended` struct wraps a [`Recall`] carrying the [`Witness`] of the current request.
+/// The actor is read from the `x-actor` header, or [`Attended::ANONYMOUS`] if missing, the
+/// request id from the `x-request-id` header, and the address from the connection if the server
+/// records it.
+#[derive(Debug, Clone)]
+pub struct Attended(pub Recall);
+
+impl Attended {
+    /// The header naming the caller making a change.
+    pub const ACTOR: HeaderName = HeaderName::from_static("x-actor");
+    /// The header holding the id of the request.
+    pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+    /// The actor recorded when the `x-actor` header is missing.
+    pub const ANONYMOUS: &'static str = "anonymous";
+
+    /// The `witness` method reads the [`Witness`] of the request from its `parts`.
+    pub fn witness(parts: &Parts) -> Witness {
+        let header = |name: &HeaderName| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let mut witness = Witness::new(&header(&Self::ACTOR).unwrap_or(Self::ANONYMOUS.into()));
+        witness.request_id = header(&Self::REQUEST_ID);
+        witness.ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+        witness
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Recall> for Attended {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        recall: &Recall,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self(recall.attended(Self::witness(parts))))
+    }
+}
@@ -0,0 +1,57 @@
+//! The `charter` module contains the [`Charter`] struct, the OpenAPI description of the routes
+//! handled in [`crate::route`], generated from the attributes on each handler and the types they
+//! accept and return.
+use crate::route;
+use cordial_guest::{Order, Sort};
+use polite::{Flaw, Problem};
+use utoipa::OpenApi;
+
+/// The `Charter` struct generates the OpenAPI 3.1 document describing each route in
+/// [`crate::route`], its parameters, request and response bodies, and error responses.  Call
+/// [`OpenApi::openapi`] for the document.  The host adds the routes it serves itself.
+#[derive(Debug, Copy, Clone, Default, OpenApi)]
+#[openapi(
+    info(
+        title = "cordial",
+        description = "Manages guests, and improvises names and passwords for them.",
+        license(name = "Apache-2.0", identifier = "Apache-2.0")
+    ),
+    paths(
+        route::book,
+        route::health,
+        route::live,
+        route::ready,
+        route::lookup_all,
+        route::check_in,
+        route::lookup_name,
+        route::lookup,
+        route::update,
+        route::check_out,
+        route::check_in_batch,
+        route::update_batch,
+        route::check_out_batch,
+        route::departed,
+        route::sweep,
+        route::purge,
+        route::restore,
+        route::audits,
+        route::guest_name,
+        route::guest_name_numbered,
+        route::guest_pass,
+        route::pass_adv,
+        route::names,
+        route::passes,
+        route::guests,
+        route::passphrase,
+        route::score,
+    ),
+    components(schemas(Flaw, Order, Problem, Sort)),
+    tags(
+        (name = "health", description = "Probes of the server and its database."),
+        (name = "guests", description = "Entering, finding, updating and checking out guests."),
+        (name = "admin", description = "Restoring and purging checked out guests, and reviewing the audit trail."),
+        (name = "improv", description = "Improvised names and passwords."),
+        (name = "docs", description = "Descriptions of the API."),
+    )
+)]
+pub struct Charter;
@@ -1,3 +1,10 @@
+pub mod attended;
+pub mod charter;
 pub mod counsel;
+pub mod route;
+pub mod vetted;
 
+pub use crate::attended::Attended;
+pub use crate::charter::Charter;
 pub use crate::counsel::Counsel;
+pub use crate::vetted::{Vet, Vetted};
@@ -0,0 +1,728 @@
+//! The `route` module contains the handler of each route served by the host.  The
+//! `#[utoipa::path]` attribute on each handler describes its route for the [`Charter`], so the
+//! description follows the handlers as they change.
+//!
+//! [`Charter`]: crate::Charter
+use crate::attended::Attended;
+use crate::counsel::Counsel;
+use crate::vetted::Vetted;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use cordial_guest::{Guest, Inquiry};
+use cordial_improv::{
+    Appraisal, Bulk, Candidate, Improv, Pass, Passphrase, Phrase, Policy, Vacancy,
+};
+use cordial_memory::Memorable;
+use cordial_recall::{Audit, Recall, Review, Vitals};
+use polite::{FauxPas, Flaw, Problem};
+use tracing::{info, trace};
+use uuid::Uuid;
+
+/// The `book` method returns the version of the postgres database, or a `503 Service
+/// Unavailable` with the error message if the database cannot be queried.
+#[utoipa::path(
+    get,
+    path = "/book",
+    tag = "health",
+    summary = "Reports the version of the database.",
+    responses(
+        (status = 200, description = "The version of the database.", body = String, content_type = "text/plain"),
+        (status = 503, description = "The database cannot be queried, with the error message.", body = String, content_type = "text/plain"),
+    )
+)]
+pub async fn book(State(recall): State<Recall>) -> impl IntoResponse {
+    info!("Checking book.");
+    trace!("Getting version");
+    let mut headers = Counsel::headers();
+    Counsel::plain(&mut headers);
+    let result: Result<String, sqlx::Error> = sqlx::query_scalar("SELECT version()")
+        .fetch_one(&recall.book)
+        .await;
+    match result {
+        Ok(version) => (StatusCode::OK, headers, version),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, headers, e.to_string()),
+    }
+}
+
+/// The `live` method returns a status OK, used to assess if the process is responsive.  Does
+/// not consult the database; see [`ready`].
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    summary = "Reports that the process is responsive, without consulting the database.",
+    responses(
+        (status = 200, description = "The process is responsive."),
+    )
+)]
+pub async fn live() -> impl IntoResponse {
+    info!("Bearing check.");
+    StatusCode::OK
+}
+
+/// The `health` method returns a status OK, the same as [`live`] under the older path.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    summary = "Reports that the process is responsive, the same as /health/live.",
+    responses(
+        (status = 200, description = "The process is responsive."),
+    )
+)]
+pub async fn health() -> impl IntoResponse {
+    live().await
+}
+
+/// The `ready` method reports the [`Vitals`] of the database as JSON, with a status OK if the
+/// server can handle requests and a `503 Service Unavailable` if the database is unreachable
+/// or has pending migrations.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    summary = "Reports the health of the database and whether the server can handle requests.",
+    responses(
+        (status = 200, description = "The server can handle requests.", body = Vitals),
+        (status = 503, description = "The database is unreachable or has pending migrations.", body = Vitals),
+    )
+)]
+pub async fn ready(State(recall): State<Recall>) -> impl IntoResponse {
+    info!("Readiness check.");
+    let vitals = recall.vitals(Vitals::TIMEOUT).await;
+    let status = match vitals.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(vitals))
+}
+
+/// The `lookup` method looks up a [`Guest`] based upon their `id`.
+#[utoipa::path(
+    get,
+    path = "/guests/{id}",
+    tag = "guests",
+    summary = "Looks up a guest by id.",
+    params(("id" = Uuid, Path, description = "The id of the guest.")),
+    responses(
+        (status = 200, description = "The guest.", body = Guest),
+        (status = 400, description = "No guest has the id.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn lookup(
+    Path(id): Path<Uuid>,
+    State(recall): State<Recall>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Getting guest {}", &id);
+    let guest = recall.get(id).await;
+    match guest {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `lookup_name` method looks up a [`Guest`] based upon their `name`, ignoring case and
+/// Unicode compatibility differences.
+#[utoipa::path(
+    get,
+    path = "/guests/by-name/{name}",
+    tag = "guests",
+    summary = "Looks up a guest by name, ignoring case and Unicode compatibility differences.",
+    params(("name" = String, Path, description = "The name of the guest.")),
+    responses(
+        (status = 200, description = "The guest.", body = Guest),
+        (status = 400, description = "No guest has the name.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn lookup_name(
+    Path(name): Path<String>,
+    State(recall): State<Recall>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Getting guest named {}", &name);
+    let guest = recall.get_by_name(&name).await;
+    match guest {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `lookup_all` method returns all [`Guest`] entries, sorted and filtered according to the
+/// [`Inquiry`] in the query string.
+#[utoipa::path(
+    get,
+    path = "/guests",
+    tag = "guests",
+    summary = "Lists the guests, sorted and filtered by the query.",
+    params(Inquiry),
+    responses(
+        (status = 200, description = "The matching guests.", body = Vec<Guest>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn lookup_all(
+    State(recall): State<Recall>,
+    Query(inquiry): Query<Inquiry>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Getting all guests.");
+    let guests = recall.inquire(&inquiry).await;
+    match guests {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `check_in` method enters a new [`Guest`] into the book under a fresh `id`, ignoring
+/// any `id` sent.  The `name` and `hash` must follow the rules declared on [`Guest`] and the
+/// password satisfy the default [`Policy`], or the request fails with a list of the rules
+/// broken.
+#[utoipa::path(
+    post,
+    path = "/guests",
+    tag = "guests",
+    summary = "Enters a new guest into the book, under an id assigned by the server.",
+    request_body = Guest,
+    responses(
+        (status = 200, description = "The guest entered.", body = Guest),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The name or password is invalid, or the password breaks the policy.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn check_in(
+    Attended(recall): Attended,
+    Vetted(mut guest): Vetted<Guest>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Checking in guest {}.", &guest.name);
+    guest.id = Uuid::new_v4();
+    if let Err(e) = Policy::default().check("hash", &guest.name, &guest.hash) {
+        return Err(Counsel::fault(e));
+    }
+    let attempt = recall.create(&guest).await;
+    match attempt {
+        Ok(created) => Ok((StatusCode::OK, Json(created))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `update` method updates the `name` and `hash` fields of the [`Guest`] with the `id` in
+/// the path, while maintain the same `id`.  A body leaving out the `id` takes the one in the
+/// path, and a body naming a different `id` is refused.  The fields must follow the rules declared
+/// on [`Guest`], and a changed password must satisfy the default [`Policy`].
+#[utoipa::path(
+    put,
+    path = "/guests/{id}",
+    tag = "guests",
+    summary = "Updates the name and password of a guest.",
+    params(("id" = Uuid, Path, description = "The id of the guest.")),
+    request_body = Guest,
+    responses(
+        (status = 200, description = "The guest updated.", body = Guest),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The id does not match the path, the name or password is invalid, or the new password breaks the policy.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update(
+    Path(id): Path<Uuid>,
+    Attended(recall): Attended,
+    Vetted(mut guest): Vetted<Guest>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Updating guest {}.", &id);
+    if guest.id.is_nil() {
+        guest.id = id;
+    } else if guest.id != id {
+        let flaw = Flaw::new("id", "match", "Must match the id in the path.");
+        return Err(Counsel::fault(FauxPas::Invalid(vec![flaw])));
+    }
+    if let Err(e) = Counsel::vet(&recall, std::slice::from_ref(&guest), |_| "hash".into()).await {
+        return Err(Counsel::fault(e));
+    }
+    let attempt = recall.update(&guest).await;
+    match attempt {
+        Ok(updated) => Ok((StatusCode::OK, Json(updated))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `check_out` method marks the [`Guest`] with the `id` in the path as deleted in the book.
+/// Deleted guests can be restored with [`restore`] until they are purged.
+#[utoipa::path(
+    delete,
+    path = "/guests/{id}",
+    tag = "guests",
+    summary = "Checks out a guest, hiding them until restored or purged.",
+    params(("id" = Uuid, Path, description = "The id of the guest.")),
+    responses(
+        (status = 200, description = "The guest checked out."),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn check_out(
+    Path(id): Path<Uuid>,
+    Attended(recall): Attended,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Checking out guest {}.", &id);
+    let guest = Guest {
+        id,
+        ..Default::default()
+    };
+    let attempt = recall.delete(&guest).await;
+    match attempt {
+        Ok(()) => Ok(StatusCode::OK),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `check_in_batch` method enters every [`Guest`] in the request body into the book as a
+/// single unit, each under a fresh `id`.  If any guest cannot be entered, none are.
+#[utoipa::path(
+    post,
+    path = "/guests/batch",
+    tag = "guests",
+    summary = "Enters every guest in the body as a single unit.",
+    request_body = Vec<Guest>,
+    responses(
+        (status = 200, description = "The guests entered.", body = Vec<Guest>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "A name or password is invalid or breaks the policy, with fields named by index.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn check_in_batch(
+    Attended(recall): Attended,
+    Vetted(mut guests): Vetted<Vec<Guest>>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Checking in {} guests.", guests.len());
+    guests
+        .iter_mut()
+        .for_each(|guest| guest.id = Uuid::new_v4());
+    let policy = Policy::default();
+    let flaws = guests
+        .iter()
+        .enumerate()
+        .filter_map(|(i, guest)| {
+            policy
+                .check(&format!("[{}].hash", i), &guest.name, &guest.hash)
+                .err()
+        })
+        .collect::<Vec<FauxPas>>();
+    if !flaws.is_empty() {
+        return Err(Counsel::fault(FauxPas::Invalid(Counsel::flaws(flaws))));
+    }
+    let attempt = recall.create_many(&guests).await;
+    match attempt {
+        Ok(created) => Ok((StatusCode::OK, Json(created))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `update_batch` method updates every [`Guest`] in the request body as a single unit.  If
+/// any guest cannot be updated, none are.
+#[utoipa::path(
+    put,
+    path = "/guests/batch",
+    tag = "guests",
+    summary = "Updates every guest in the body as a single unit.",
+    request_body = Vec<Guest>,
+    responses(
+        (status = 200, description = "The guests updated.", body = Vec<Guest>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "A name or new password is invalid or breaks the policy, with fields named by index.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn update_batch(
+    Attended(recall): Attended,
+    Vetted(guests): Vetted<Vec<Guest>>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Updating {} guests.", guests.len());
+    if let Err(e) = Counsel::vet(&recall, &guests, |i| format!("[{}].hash", i)).await {
+        return Err(Counsel::fault(e));
+    }
+    let attempt = recall.update_many(&guests).await;
+    match attempt {
+        Ok(updated) => Ok((StatusCode::OK, Json(updated))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `check_out_batch` method marks every [`Guest`] in the request body as deleted.
+#[utoipa::path(
+    delete,
+    path = "/guests/batch",
+    tag = "guests",
+    summary = "Checks out every guest in the body.",
+    request_body = Vec<Guest>,
+    responses(
+        (status = 200, description = "The guests checked out."),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn check_out_batch(
+    Attended(recall): Attended,
+    Json(guests): Json<Vec<Guest>>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Checking out {} guests.", guests.len());
+    let attempt = recall.delete_many(&guests).await;
+    match attempt {
+        Ok(()) => Ok(StatusCode::OK),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `departed` method returns all deleted [`Guest`] entries that have not been purged.
+#[utoipa::path(
+    get,
+    path = "/admin/guests/deleted",
+    tag = "admin",
+    summary = "Lists the checked out guests not yet purged.",
+    responses(
+        (status = 200, description = "The deleted guests.", body = Vec<Guest>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn departed(
+    State(recall): State<Recall>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Getting deleted guests.");
+    let guests = recall.deleted().await;
+    match guests {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `restore` method returns a deleted [`Guest`] to the book.
+#[utoipa::path(
+    post,
+    path = "/admin/guests/{id}/restore",
+    tag = "admin",
+    summary = "Returns a checked out guest to the book.",
+    params(("id" = Uuid, Path, description = "The id of the guest.")),
+    responses(
+        (status = 200, description = "The guest restored.", body = Guest),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn restore(
+    Path(id): Path<Uuid>,
+    Attended(recall): Attended,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Restoring guest {}.", &id);
+    let attempt = recall.restore(id).await;
+    match attempt {
+        Ok(restored) => Ok((StatusCode::OK, Json(restored))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `purge` method permanently removes a deleted [`Guest`] from the book.
+#[utoipa::path(
+    delete,
+    path = "/admin/guests/{id}",
+    tag = "admin",
+    summary = "Permanently removes a checked out guest.",
+    params(("id" = Uuid, Path, description = "The id of the guest.")),
+    responses(
+        (status = 200, description = "The guest purged."),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn purge(
+    Path(id): Path<Uuid>,
+    Attended(recall): Attended,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Purging guest {}.", &id);
+    let attempt = recall.purge(id).await;
+    match attempt {
+        Ok(()) => Ok(StatusCode::OK),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `sweep` method purges every deleted [`Guest`] past the retention period, returning the
+/// number of entries removed.
+#[utoipa::path(
+    post,
+    path = "/admin/guests/sweep",
+    tag = "admin",
+    summary = "Purges every checked out guest past the retention period.",
+    responses(
+        (status = 200, description = "The number of guests purged.", body = u64),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn sweep(Attended(recall): Attended) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Sweeping expired guests.");
+    let attempt = recall.sweep().await;
+    match attempt {
+        Ok(count) => Ok((StatusCode::OK, Json(count))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `audits` method lists the [`cordial_recall::Audit`] entries matching the filters in
+/// the query string, newest first.  Changes are attributed to the caller named in the `X-Actor`
+/// header of the request making them.  Fails with a `422 Unprocessable Entity` if the `limit` is
+/// out of range.
+#[utoipa::path(
+    get,
+    path = "/admin/audits",
+    tag = "admin",
+    summary = "Lists the audit trail of changes to guests, newest first.",
+    params(Review),
+    responses(
+        (status = 200, description = "The matching entries.  Pass the id of the last as the cursor for the next page.", body = Vec<Audit>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The limit is out of range.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn audits(
+    State(recall): State<Recall>,
+    Query(review): Query<Review>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Reviewing audits.");
+    let attempt = recall.audits(&review).await;
+    match attempt {
+        Ok(audits) => Ok((StatusCode::OK, Json(audits))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `guest_name` method offers a recommendation for the `name` of a [`Guest`].  The
+/// [`Vacancy`] in the query string can request names not yet taken in the book, several names,
+/// names close to a requested one, or names following a [`cordial_improv::Pattern`].  Returns
+/// a single name as plain text, or a JSON array if `near` or `count` is present.
+#[utoipa::path(
+    get,
+    path = "/improv/name",
+    tag = "improv",
+    summary = "Recommends a name, or several names.",
+    params(Vacancy),
+    responses(
+        (status = 200, description = "A name as plain text, or a JSON array of names if `near` or `count` is set.", content((String = "text/plain"), (Vec<String> = "application/json"))),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The pattern or count is invalid.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn guest_name(
+    State(recall): State<Recall>,
+    Query(vacancy): Query<Vacancy>,
+) -> Result<Response, Response> {
+    info!("Recommending guest name.");
+    if vacancy.near.is_some() || vacancy.count.is_some() {
+        let attempt = vacancy.offer(&recall).await;
+        return match attempt {
+            Ok(result) => Ok((StatusCode::OK, Json(result)).into_response()),
+            Err(e) => Err(Counsel::fault(e)),
+        };
+    }
+    let attempt = match vacancy.available {
+        true => vacancy.name(&recall).await,
+        false => vacancy.improvise(1).map(|mut names| names.remove(0)),
+    };
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, result).into_response()),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `guest_name_numbered` method offers a recommendation for a numbered `name` of a [`Guest`].
+#[utoipa::path(
+    get,
+    path = "/improv/name/num",
+    tag = "improv",
+    summary = "Recommends a numbered name.",
+    responses(
+        (status = 200, description = "A numbered name.", body = String, content_type = "text/plain"),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn guest_name_numbered() -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending numbered guest name.");
+    let mut improv = Improv::new(true);
+    let attempt = improv.name();
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, result)),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `guest_pass` method offers a recommendation for the `pass` of a [`Guest`].
+#[utoipa::path(
+    get,
+    path = "/improv/pass",
+    tag = "improv",
+    summary = "Recommends a password.",
+    responses(
+        (status = 200, description = "A password.", body = String, content_type = "text/plain"),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn guest_pass() -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending guest pass.");
+    let mut improv = Improv::new(false);
+    let attempt = improv.pass();
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, result)),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `passphrase` method offers a passphrase recommendation for a [`Guest`], using the
+/// [`Passphrase`] configuration provided in the request body, along with its entropy in bits.
+#[utoipa::path(
+    post,
+    path = "/improv/passphrase",
+    tag = "improv",
+    summary = "Recommends a passphrase.",
+    request_body = Passphrase,
+    responses(
+        (status = 200, description = "A passphrase and its entropy.", body = Phrase),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn passphrase(
+    Json(config): Json<Passphrase>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending passphrase.");
+    let mut improv = Improv::new(false);
+    let attempt = improv.passphrase(&config);
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::refuse(e)),
+    }
+}
+
+/// The `score` method appraises the password in the request body against the default
+/// [`Policy`], returning its score, a rating and any rules it breaks.
+#[utoipa::path(
+    post,
+    path = "/improv/score",
+    tag = "improv",
+    summary = "Appraises a password against the default policy.",
+    request_body = Candidate,
+    responses(
+        (status = 200, description = "The appraisal of the password.", body = Appraisal),
+    )
+)]
+pub async fn score(Json(candidate): Json<Candidate>) -> impl IntoResponse {
+    info!("Scoring candidate pass.");
+    let appraisal = Policy::default().appraise("pass", candidate.name.as_deref(), &candidate.pass);
+    (StatusCode::OK, Json(appraisal))
+}
+
+/// The `pass_adv` method offers a recommendation for the `pass` of a [`Guest`] using the
+/// configuration provided in the request body.  Rejects configurations that fail
+/// [`Pass::validate`] with the list of rules broken.
+#[utoipa::path(
+    post,
+    path = "/improv/pass",
+    tag = "improv",
+    summary = "Recommends a password following the configuration in the body.",
+    request_body = Pass,
+    responses(
+        (status = 200, description = "A password.", body = String, content_type = "text/plain"),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The configuration is invalid.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn pass_adv(Json(config): Json<Pass>) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending custom pass.");
+    if let Err(e) = config.validate() {
+        return Err(Counsel::fault(e));
+    }
+    let mut improv = Improv::from_pass(config.into(), false);
+    let attempt = improv.pass();
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, result)),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `names` method offers a JSON array of numbered names, with the number and seed given
+/// by the [`Bulk`] in the query string.
+#[utoipa::path(
+    get,
+    path = "/improv/names",
+    tag = "improv",
+    summary = "Recommends several numbered names.",
+    params(Bulk),
+    responses(
+        (status = 200, description = "The names.", body = Vec<String>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The count is out of range.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn names(Query(bulk): Query<Bulk>) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending {} names.", bulk.count);
+    if let Err(e) = bulk.validate() {
+        return Err(Counsel::fault(e));
+    }
+    let attempt = bulk.improv().names(bulk.count);
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `passes` method offers a JSON array of passwords following the [`Pass`] configuration
+/// in the request body, with the number and seed given by the [`Bulk`] in the query string.
+#[utoipa::path(
+    post,
+    path = "/improv/passes",
+    tag = "improv",
+    summary = "Recommends several passwords following the configuration in the body.",
+    params(Bulk),
+    request_body = Pass,
+    responses(
+        (status = 200, description = "The passwords.", body = Vec<String>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The count or configuration is invalid.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn passes(
+    Query(bulk): Query<Bulk>,
+    Json(config): Json<Pass>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending {} passes.", bulk.count);
+    let errors = [bulk.validate(), config.validate()]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect::<Vec<FauxPas>>();
+    if !errors.is_empty() {
+        return Err(Counsel::fault(FauxPas::Invalid(Counsel::flaws(errors))));
+    }
+    let mut improv = bulk.improv();
+    improv.pass = config.into();
+    let attempt = improv.passes(bulk.count);
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
+
+/// The `guests` method offers a JSON array of [`Guest`] entries with improvised names and
+/// passwords, with the number and seed given by the [`Bulk`] in the query string.  The guests
+/// are not entered into the book.
+#[utoipa::path(
+    get,
+    path = "/improv/guests",
+    tag = "improv",
+    summary = "Improvises several guests without entering them into the book.",
+    params(Bulk),
+    responses(
+        (status = 200, description = "The guests, not entered into the book.", body = Vec<Guest>),
+        (status = 400, description = "The request failed, with the error message.", body = Problem, content_type = "application/problem+json"),
+        (status = 422, description = "The count is out of range.", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn guests(Query(bulk): Query<Bulk>) -> Result<impl IntoResponse, impl IntoResponse> {
+    info!("Recommending {} guests.", bulk.count);
+    if let Err(e) = bulk.validate() {
+        return Err(Counsel::fault(e));
+    }
+    let attempt = bulk.improv().guests(bulk.count);
+    match attempt {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(e) => Err(Counsel::fault(e)),
+    }
+}
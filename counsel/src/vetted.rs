@@ -0,0 +1,75 @@
+//! The `vetted` module contains the [`Vetted`] extractor, which reads a JSON body and checks it
+//! against the rules declared on its type before the handler runs, and the [`Vet`] trait naming
+//! the types it checks.
+use crate::counsel::Counsel;
+use axum::async_trait;
+use axum::extract::{FromRequest, Request};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use cordial_guest::Guest;
+use polite::{FauxPas, Polite};
+use validator::Validate;
+
+/// The `Vet` trait checks a value against the rules declared on its type, committing a
+/// [`FauxPas::Invalid`] listing each [`polite::Flaw`] found.
+pub trait Vet {
+    /// The `vet` method checks the value, naming each flawed field.
+    fn vet(&self) -> Polite<()>;
+}
+
+impl Vet for Guest {
+    fn vet(&self) -> Polite<()> {
+        Ok(self.validate()?)
+    }
+}
+
+/// A list is vetted entry by entry, naming the fields of each flawed entry by its index, such as
+/// `[1].name`.
+impl<T: Vet> Vet for Vec<T> {
+    fn vet(&self) -> Polite<()> {
+        let mut flaws = Vec::new();
+        for (i, entry) in self.iter().enumerate() {
+            match entry.vet() {
+                Ok(()) => {}
+                Err(FauxPas::Invalid(found)) => {
+                    flaws.extend(found.into_iter().map(|mut flaw| {
+                        flaw.field = match flaw.field.is_empty() {
+                            true => format!("[{}]", i),
+                            false => format!("[{}].{}", i, flaw.field),
+                        };
+                        flaw
+                    }));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        match flaws.is_empty() {
+            true => Ok(()),
+            false => Err(FauxPas::Invalid(flaws)),
+        }
+    }
+}
+
+/// The `Vetted` struct wraps a JSON body of type `T` that passed [`Vet::vet`].  A body that
+/// cannot be read is refused as by [`Json`], and a body breaking the rules of `T` with a `422
+/// Unprocessable Entity` listing each [`polite::Flaw`].  See [`Counsel::fault`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vetted<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Vetted<T>
+where
+    T: Vet,
+    Json<T>: FromRequest<S>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        value.vet().map_err(Counsel::fault)?;
+        Ok(Self(value))
+    }
+}
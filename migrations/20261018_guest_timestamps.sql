@@ -0,0 +1,19 @@
+ALTER TABLE guests
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN last_login_at TIMESTAMPTZ;
+
+CREATE OR REPLACE FUNCTION touch_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER guests_updated_at
+    BEFORE UPDATE OF name, hash ON guests
+    FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+
+CREATE INDEX guests_created_at_idx ON guests (created_at);
+CREATE INDEX guests_updated_at_idx ON guests (updated_at);
+CREATE INDEX guests_last_login_at_idx ON guests (last_login_at);
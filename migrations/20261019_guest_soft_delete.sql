@@ -0,0 +1,4 @@
+ALTER TABLE guests
+    ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX guests_deleted_at_idx ON guests (deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,4 @@
+-- The keys are filled in by `Posture::migrate` with `Guest::fold`, which Postgres cannot express,
+-- before 20261021_guest_name_key_index makes them required and unique.
+ALTER TABLE guests
+    ADD COLUMN name_key TEXT;
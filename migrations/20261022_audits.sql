@@ -0,0 +1,25 @@
+CREATE TABLE audits (
+    id BIGSERIAL PRIMARY KEY,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    actor TEXT NOT NULL,
+    guest_id UUID NOT NULL,
+    action TEXT NOT NULL CHECK (
+        action IN ('create', 'update', 'delete', 'restore', 'purge', 'login', 'login_failed')
+    ),
+    diff JSONB NOT NULL DEFAULT '{}',
+    request_id TEXT,
+    ip TEXT
+);
+
+CREATE INDEX audits_guest_id_idx ON audits (guest_id, id);
+CREATE INDEX audits_recorded_at_idx ON audits (recorded_at);
+
+CREATE FUNCTION audits_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audits are append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audits_append_only
+    BEFORE UPDATE OR DELETE ON audits
+    FOR EACH ROW EXECUTE FUNCTION audits_append_only();
@@ -1,6 +1,6 @@
 pub mod polite;
 
-pub use polite::{FauxPas, Polite};
+pub use polite::{FauxPas, Flaw, Polite, Problem};
 
 #[cfg(feature = "parse")]
 #[cfg_attr(docsrs, doc(cfg(feature = "parse")))]
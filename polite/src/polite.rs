@@ -17,6 +17,10 @@ pub enum FauxPas {
     /// The `BadTest` variant indicates a test failed, used by inner test functions.
     #[error("Test failed.")]
     BadTest,
+    /// The `Closed` variant indicates use of a handle, such as a transaction, that has already
+    /// been closed.
+    #[error("Handle already closed: {0}")]
+    Closed(String),
     /// The `Env` variant represents error conversions from [`std::env::VarError`].
     #[error("Could not read environmental variables from .env: {0}")]
     Env(#[from] std::env::VarError),
@@ -27,6 +31,10 @@ pub enum FauxPas {
     /// libraries `names` and `passwords`.
     #[error("Generator option yielded none.")]
     Improv(String),
+    /// The `Invalid` variant indicates input that breaks one or more rules, with a [`Flaw`]
+    /// describing each.
+    #[error("Invalid input: {}", Flaw::summary(.0))]
+    Invalid(Vec<Flaw>),
     /// The `Int` variant represents error conversions from [`std::num::ParseIntError`],
     /// indicating a failure to parse an integer from a string.
     #[error("Could not parse integer from string: {0}")]
@@ -41,6 +49,15 @@ pub enum FauxPas {
     /// libraries `names` and `passwords`.
     #[error("Password generation error: {0}")]
     Pass(String),
+    /// The `Status` variant indicates a server answered a request with an error status, holding
+    /// the status code and the message from the response body, the `detail` of a [`Problem`].
+    #[error("Request failed with status {status}: {message}")]
+    Status {
+        /// The `status` field holds the HTTP status code of the response.
+        status: u16,
+        /// The `message` field holds the `detail` of a [`Problem`] body, or else the whole body.
+        message: String,
+    },
     /// The `UserBuild` indicates an error occurred using a builder pattern.
     #[error("Value not provided for {value:?}.")]
     UserBuild {
@@ -138,6 +155,12 @@ pub enum FauxPas {
     #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
     #[error("Problem initializing subscriber: {0}")]
     TraceInit(#[from] tracing_subscriber::util::TryInitError),
+    /// The `Otlp` variant converts an error building a trace exporter from the
+    /// `opentelemetry_otlp` crate.
+    #[cfg(feature = "otel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "otel")))]
+    #[error("Problem building trace exporter: {0}")]
+    Otlp(#[from] opentelemetry_otlp::ExporterBuildError),
     /// The `Axum` variants converts an *axum::Error* from the `axum` crate.
     #[cfg(feature = "route")]
     #[cfg_attr(docsrs, doc(cfg(feature = "route")))]
@@ -220,6 +243,160 @@ pub enum FauxPas {
     Unknown,
 }
 
+/// The `Flaw` struct describes a single rule broken by a field of the input, carried by
+/// [`FauxPas::Invalid`].
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Flaw {
+    /// The `field` field names the input field at fault.
+    pub field: String,
+    /// The `rule` field holds a stable, machine-readable name for the broken rule.
+    pub rule: String,
+    /// The `message` field holds a human-readable description of the problem.
+    pub message: String,
+}
+
+impl Flaw {
+    /// Creates a new `Flaw` for `field`, breaking `rule`, described by `message`.
+    pub fn new(field: &str, rule: &str, message: &str) -> Self {
+        Self {
+            field: field.to_owned(),
+            rule: rule.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// The `summary` method joins the messages of `flaws` into a single line.
+    pub fn summary(flaws: &[Flaw]) -> String {
+        flaws
+            .iter()
+            .map(|flaw| flaw.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for Flaw {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// The `Problem` struct is the body of an error response, after the problem details of RFC 9457.
+/// A refused request lists each [`Flaw`] of the input, and carries the id of the request so a
+/// report can be matched with the logs of the server.
+#[cfg_attr(feature = "serial", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Problem {
+    /// The `status` field holds the HTTP status code of the response.
+    pub status: u16,
+    /// The `title` field holds a short summary of the kind of problem, the reason phrase of the
+    /// `status`.
+    pub title: String,
+    /// The `detail` field holds a human-readable explanation of this occurrence of the problem.
+    pub detail: String,
+    /// The `flaws` field lists each rule broken by the input, empty unless the input was invalid.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub flaws: Vec<Flaw>,
+    /// The `request_id` field holds the id of the request that failed, if known.
+    #[cfg_attr(feature = "serial", serde(default))]
+    pub request_id: Option<String>,
+}
+
+impl Problem {
+    /// The media type of a `Problem` body.
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    /// Creates a new `Problem` answered with `status`, summarized by `title` and explained by
+    /// `detail`, with no flaws or request id.  Modify the fields directly after construction to
+    /// customize.
+    pub fn new(status: u16, title: &str, detail: &str) -> Self {
+        Self {
+            status,
+            title: title.to_owned(),
+            detail: detail.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.status, self.title, self.detail)
+    }
+}
+
+#[cfg(feature = "valid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "valid")))]
+impl Flaw {
+    /// The `vetted` method lists a `Flaw` for each rule in `errors` from the `validator` crate,
+    /// naming nested fields with dots and list entries by index, such as `items[0].name`, below
+    /// `prefix`.  The `code` of each error becomes the `rule`, and its message the `message`.
+    /// Errors without a message are described as by [`Flaw::length`] for the `length` rule, and
+    /// by naming the rule otherwise.
+    pub fn vetted(prefix: &str, errors: &validator::ValidationErrors) -> Vec<Flaw> {
+        use validator::ValidationErrorsKind;
+        let join = |field: &str| match prefix.is_empty() {
+            true => field.to_owned(),
+            false => format!("{}.{}", prefix, field),
+        };
+        let mut fields = errors.errors().iter().collect::<Vec<_>>();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+        let mut flaws = Vec::new();
+        for (field, kind) in fields {
+            let field = join(field);
+            match kind {
+                ValidationErrorsKind::Field(errors) => {
+                    flaws.extend(errors.iter().map(|error| {
+                        let message = match (&error.message, error.code.as_ref()) {
+                            (Some(message), _) => message.to_string(),
+                            (None, "length") => Flaw::length(error),
+                            (None, code) => format!("Breaks the {} rule.", code),
+                        };
+                        Flaw::new(&field, &error.code, &message)
+                    }));
+                }
+                ValidationErrorsKind::Struct(errors) => {
+                    flaws.extend(Flaw::vetted(&field, errors));
+                }
+                ValidationErrorsKind::List(entries) => {
+                    for (i, errors) in entries {
+                        flaws.extend(Flaw::vetted(&format!("{}[{}]", field, i), errors));
+                    }
+                }
+            }
+        }
+        flaws
+    }
+
+    /// The `length` method describes a broken `length` rule from the bounds held in the `params`
+    /// of `error`, such as `Must be from 1 to 64 characters.`
+    pub fn length(error: &validator::ValidationError) -> String {
+        let bound = |name: &str| error.params.get(name).and_then(|value| value.as_u64());
+        match (bound("equal"), bound("min"), bound("max")) {
+            (Some(equal), _, _) => format!("Must be exactly {} characters.", equal),
+            (None, Some(min), Some(max)) => format!("Must be from {} to {} characters.", min, max),
+            (None, Some(min), None) => format!("Must be at least {} characters.", min),
+            (None, None, Some(max)) => format!("Must be at most {} characters.", max),
+            (None, None, None) => "Breaks the length rule.".to_owned(),
+        }
+    }
+}
+
+#[cfg(feature = "valid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "valid")))]
+impl From<validator::ValidationErrors> for FauxPas {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        Self::Invalid(Flaw::vetted("", &errors))
+    }
+}
+
 #[cfg(feature = "parse")]
 #[cfg_attr(docsrs, doc(cfg(feature = "parse")))]
 impl<'a> From<nom::Err<nom::error::Error<&'a str>>> for FauxPas {
@@ -0,0 +1,94 @@
+//! The `db` module holds the subcommands managing the database named in the [`Posture`].
+use crate::output::{Format, Table};
+use clap::Subcommand;
+use cordial::prelude::*;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug, Subcommand)]
+pub enum Db {
+    /// Create the database.
+    Create,
+    /// Apply any pending migrations.
+    Migrate,
+    /// Report whether the database exists and which migrations are applied.
+    Status,
+    /// Drop the database and every guest in it.
+    Drop {
+        /// Skip the confirmation prompt.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// The `Status` struct reports the state of the database.
+#[derive(Debug, Serialize)]
+struct Status {
+    database: String,
+    exists: bool,
+    migrations: Vec<Milestone>,
+}
+
+impl Db {
+    pub async fn run(&self, posture: &Posture, format: Format) -> Polite<()> {
+        match self {
+            Self::Create => {
+                posture.create().await?;
+                posture.migrate().await?;
+                println!("Created database {}.", &posture.database);
+            }
+            Self::Migrate => {
+                posture.migrate().await?;
+                println!("Migrated database {}.", &posture.database);
+            }
+            Self::Status => {
+                let exists = posture.exists().await?;
+                let migrations = match exists {
+                    true => posture.status().await?,
+                    false => Vec::new(),
+                };
+                let status = Status {
+                    database: posture.database.clone(),
+                    exists,
+                    migrations,
+                };
+                format.emit(&status, || {
+                    let mut table = Table::new(&["VERSION", "DESCRIPTION", "APPLIED"]);
+                    for milestone in &status.migrations {
+                        table.row(vec![
+                            milestone.version.to_string(),
+                            milestone.description.clone(),
+                            milestone.applied.to_string(),
+                        ]);
+                    }
+                    if !status.exists {
+                        table.row(vec![
+                            "-".to_string(),
+                            format!("database {} does not exist", &status.database),
+                            "-".to_string(),
+                        ]);
+                    }
+                    table
+                })?;
+            }
+            Self::Drop { yes } => {
+                if !yes && !Self::confirm(&posture.database)? {
+                    println!("Left database {} in place.", &posture.database);
+                    return Ok(());
+                }
+                posture.delete().await?;
+                println!("Dropped database {}.", &posture.database);
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks the user to type the name of the database before dropping it.
+    fn confirm(database: &str) -> Polite<bool> {
+        print!("Type the database name ({}) to drop it: ", database);
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(answer.trim() == database)
+    }
+}
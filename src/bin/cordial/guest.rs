@@ -0,0 +1,178 @@
+//! The `guest` module holds the subcommands managing the [`Guest`] entries in the book.
+use crate::output::{Format, Table};
+use clap::{Subcommand, ValueEnum};
+use cordial::prelude::*;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum Visit {
+    /// List the guests in the book.
+    List {
+        /// The field to sort by.
+        #[arg(long, value_enum, default_value_t = Field::CreatedAt)]
+        sort: Field,
+        /// Sort from largest to smallest.
+        #[arg(long)]
+        desc: bool,
+        /// List the deleted guests awaiting purge instead.
+        #[arg(long)]
+        deleted: bool,
+    },
+    /// Show a guest, found by id or name.
+    Show {
+        /// The id or name of the guest.
+        guest: String,
+    },
+    /// Add a guest.  Improvises a password if none is given.
+    Add {
+        /// The name of the guest.
+        name: String,
+        /// The password of the guest.
+        #[arg(long)]
+        pass: Option<String>,
+    },
+    /// Rename a guest, found by id or name.
+    Rename {
+        /// The id or name of the guest.
+        guest: String,
+        /// The new name.
+        name: String,
+    },
+    /// Set the password of a guest, found by id or name.  Improvises a password if none is given.
+    ResetPassword {
+        /// The id or name of the guest.
+        guest: String,
+        /// The new password.
+        #[arg(long)]
+        pass: Option<String>,
+    },
+    /// Remove a guest, found by id or name.
+    Remove {
+        /// The id or name of the guest.
+        guest: String,
+        /// Remove the guest permanently instead of marking it as deleted.
+        #[arg(long)]
+        purge: bool,
+    },
+}
+
+/// The `Field` enum names the fields available to the `--sort` option, matching [`Sort`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Field {
+    Name,
+    CreatedAt,
+    UpdatedAt,
+    LastLoginAt,
+}
+
+impl From<Field> for Sort {
+    fn from(field: Field) -> Self {
+        match field {
+            Field::Name => Sort::Name,
+            Field::CreatedAt => Sort::CreatedAt,
+            Field::UpdatedAt => Sort::UpdatedAt,
+            Field::LastLoginAt => Sort::LastLoginAt,
+        }
+    }
+}
+
+impl Visit {
+    pub async fn run(&self, posture: &Posture, format: Format) -> Polite<()> {
+        let recall = Recall::from(posture);
+        match self {
+            Self::List {
+                sort,
+                desc,
+                deleted,
+            } => {
+                let guests = match deleted {
+                    true => recall.deleted().await?,
+                    false => {
+                        let inquiry = Inquiry {
+                            sort: Some((*sort).into()),
+                            order: Some(if *desc { Order::Desc } else { Order::Asc }),
+                            ..Default::default()
+                        };
+                        recall.inquire(&inquiry).await?
+                    }
+                };
+                format.emit(&guests, || Table::guests(&guests))
+            }
+            Self::Show { guest } => {
+                let guest = Self::find(&recall, guest).await?;
+                format.emit(&guest, || Table::guests(std::slice::from_ref(&guest)))
+            }
+            Self::Add { name, pass } => {
+                let pass = Self::pass(name, pass)?;
+                let guest = recall.create(&Guest::new(name, &pass)).await?;
+                format.emit(&guest, || Self::credentials(&guest))
+            }
+            Self::Rename { guest, name } => {
+                let mut guest = Self::find(&recall, guest).await?;
+                guest.name = name.clone();
+                let guest = recall.update(&guest).await?;
+                format.emit(&guest, || Table::guests(std::slice::from_ref(&guest)))
+            }
+            Self::ResetPassword { guest, pass } => {
+                let mut guest = Self::find(&recall, guest).await?;
+                guest.hash = Self::pass(&guest.name, pass)?;
+                let guest = recall.update(&guest).await?;
+                format.emit(&guest, || Self::credentials(&guest))
+            }
+            Self::Remove { guest, purge } => {
+                let guest = Self::find(&recall, guest).await?;
+                recall.delete(&guest).await?;
+                if *purge {
+                    recall.purge(guest.id).await?;
+                }
+                let removed = match purge {
+                    true => guest,
+                    false => recall
+                        .deleted()
+                        .await?
+                        .into_iter()
+                        .find(|entry| entry.id == guest.id)
+                        .unwrap_or(guest),
+                };
+                format.emit(&removed, || Table::guests(std::slice::from_ref(&removed)))
+            }
+        }
+    }
+
+    /// Finds an active guest by id if `key` parses as one, and by name otherwise.
+    async fn find(recall: &Recall, key: &str) -> Polite<Guest> {
+        match Uuid::parse_str(key) {
+            Ok(id) => recall.get(id).await,
+            Err(_) => recall.get_by_name(key).await,
+        }
+    }
+
+    /// Returns `pass` if it satisfies the default [`Policy`] for a guest called `name`, or
+    /// improvises a strong password if `pass` is `None`.
+    fn pass(name: &str, pass: &Option<String>) -> Polite<String> {
+        match pass {
+            Some(pass) => {
+                Policy::default().check("pass", name, pass)?;
+                Ok(pass.clone())
+            }
+            None => {
+                let mut config = Pass::new();
+                config.length = 16;
+                config.uppercase = true;
+                config.strict = true;
+                Improv::from_pass(config.into(), false).pass()
+            }
+        }
+    }
+
+    /// A table showing the name and password of `guest`, for passing on to the guest.
+    fn credentials(guest: &Guest) -> Table {
+        let mut table = Table::new(&["ID", "NAME", "PASSWORD"]);
+        table.row(vec![
+            guest.id.to_string(),
+            guest.name.clone(),
+            guest.hash.clone(),
+        ]);
+        table
+    }
+}
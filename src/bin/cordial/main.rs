@@ -0,0 +1,75 @@
+//! The `cordial` binary offers administrative commands over the guest book configured in `.env`:
+//! managing the database, managing guests, seeding fixtures and running the server.
+mod db;
+mod guest;
+mod output;
+mod seed;
+mod serve;
+
+use clap::{Parser, Subcommand};
+use cordial::prelude::*;
+use std::process::ExitCode;
+
+/// Administrative commands for the cordial guest book.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The format of the output.
+    #[arg(long, value_enum, default_value_t = output::Format::Table, global = true)]
+    format: output::Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create, migrate, inspect or drop the database.
+    #[command(subcommand)]
+    Db(db::Db),
+    /// List, show, add, rename, reset the password of or remove guests.
+    #[command(subcommand)]
+    Guest(guest::Visit),
+    /// Populate the book with reproducible guests from a seed.
+    Seed(seed::Sow),
+    /// Run the server.
+    Serve(serve::Serve),
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.start().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+impl Cli {
+    /// Installs the tracing subscriber, exporting spans if the [`Courier`] of the [`Venue`] is
+    /// enabled, then runs the command.  The last spans are sent before returning.
+    async fn start(&self) -> Polite<()> {
+        let venue = Venue::from_env()?;
+        let provider = venue.courier.dispatch()?;
+        Usher::subscribe("cordial=info", provider.as_ref()).ok();
+        let result = self.run(venue).await;
+        if let Some(provider) = provider {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!("Traces not sent: {}", e);
+            }
+        }
+        result
+    }
+
+    async fn run(&self, venue: Venue) -> Polite<()> {
+        let posture = Posture::from_env()?;
+        match &self.command {
+            Command::Db(db) => db.run(&posture, self.format).await,
+            Command::Guest(visit) => visit.run(&posture, self.format).await,
+            Command::Seed(sow) => sow.run(&posture, self.format).await,
+            Command::Serve(serve) => serve.run(posture, venue).await,
+        }
+    }
+}
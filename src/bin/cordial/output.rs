@@ -0,0 +1,92 @@
+//! The `output` module renders command results as aligned text tables or JSON.
+use clap::ValueEnum;
+use cordial::prelude::*;
+use serde::Serialize;
+use std::io::Write;
+
+/// The `Format` enum selects how command results are printed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns with a header row.
+    Table,
+    /// Pretty-printed JSON.
+    Json,
+}
+
+impl Format {
+    /// Prints `value` as JSON, or as the [`Table`] built by `table`.
+    pub fn emit<T: Serialize>(&self, value: &T, table: impl FnOnce() -> Table) -> Polite<()> {
+        let mut stdout = std::io::stdout().lock();
+        match self {
+            Self::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(value)?)?,
+            Self::Table => write!(stdout, "{}", table())?,
+        }
+        Ok(())
+    }
+}
+
+/// The `Table` struct holds a header and rows of text, printed with each column padded to its
+/// widest cell.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(header: &[&str]) -> Self {
+        Self {
+            header: header.iter().map(|cell| cell.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// A table of `guests`, leaving out the password hash.
+    pub fn guests(guests: &[Guest]) -> Self {
+        let mut table = Self::new(&["ID", "NAME", "CREATED", "UPDATED", "LAST LOGIN", "DELETED"]);
+        let stamp = |time: Option<chrono::DateTime<chrono::Utc>>| match time {
+            Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => "-".to_string(),
+        };
+        for guest in guests {
+            table.row(vec![
+                guest.id.to_string(),
+                guest.name.clone(),
+                stamp(Some(guest.created_at)),
+                stamp(Some(guest.updated_at)),
+                stamp(guest.last_login_at),
+                stamp(guest.deleted_at),
+            ]);
+        }
+        table
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut widths = self
+            .header
+            .iter()
+            .map(|cell| cell.chars().count())
+            .collect::<Vec<usize>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
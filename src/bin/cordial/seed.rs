@@ -0,0 +1,65 @@
+//! The `seed` module holds the subcommand planting [`Rehearsal`] fixtures in the book.
+use crate::output::{Format, Table};
+use clap::Args;
+use cordial::prelude::*;
+
+/// The settings of a [`Rehearsal`], read from the command line.
+#[derive(Debug, Args)]
+pub struct Sow {
+    /// The seed producing the guests.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// The number of guests to create.
+    #[arg(long, default_value_t = 100)]
+    count: usize,
+    /// The number of guests created per batch.
+    #[arg(long, default_value_t = Rehearsal::BATCH)]
+    batch: usize,
+    /// The length of the passwords.
+    #[arg(long, default_value_t = Pass::default().length)]
+    length: usize,
+    /// Include uppercase letters in the passwords.
+    #[arg(long)]
+    uppercase: bool,
+    /// Include symbols in the passwords.
+    #[arg(long)]
+    symbols: bool,
+    /// Require every enabled character class in each password.
+    #[arg(long)]
+    strict: bool,
+    /// Remove the guests planted by the seed and plant them again.
+    #[arg(long)]
+    reset: bool,
+}
+
+impl Sow {
+    fn rehearsal(&self) -> Rehearsal {
+        let mut rehearsal = Rehearsal::new(self.seed, self.count);
+        rehearsal.batch = self.batch;
+        rehearsal.pass.length = self.length;
+        rehearsal.pass.uppercase = self.uppercase;
+        rehearsal.pass.symbols = self.symbols;
+        rehearsal.pass.strict = self.strict;
+        rehearsal
+    }
+
+    pub async fn run(&self, posture: &Posture, format: Format) -> Polite<()> {
+        posture.migrate().await?;
+        let recall = Recall::from(posture);
+        let rehearsal = self.rehearsal();
+        let seeding = match self.reset {
+            true => rehearsal.reset(&recall).await?,
+            false => rehearsal.seed(&recall).await?,
+        };
+        format.emit(&seeding, || {
+            let mut table = Table::new(&["SEED", "COUNT", "PLANTED AT", "CREATED"]);
+            table.row(vec![
+                seeding.seed.to_string(),
+                seeding.count.to_string(),
+                seeding.planted_at.to_rfc3339(),
+                seeding.planted.to_string(),
+            ]);
+            table
+        })
+    }
+}
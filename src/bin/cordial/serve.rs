@@ -0,0 +1,30 @@
+//! The `serve` module holds the subcommand running the server.
+use clap::Args;
+use cordial::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Args)]
+pub struct Serve {
+    /// The address to listen on, overriding `HOST_ADDR` [default: 127.0.0.1:8000].
+    #[arg(long)]
+    addr: Option<String>,
+    /// Seconds allowed for open connections to finish after SIGINT or SIGTERM, overriding
+    /// `HOST_GRACE_SECS` [default: 30].
+    #[arg(long)]
+    grace: Option<u64>,
+}
+
+impl Serve {
+    /// Migrates the database, then serves the routes of the [`Host`] at the `venue` until
+    /// interrupted.  The existing database is kept.
+    pub async fn run(&self, posture: Posture, mut venue: Venue) -> Polite<()> {
+        if let Some(addr) = &self.addr {
+            venue.addr = addr.clone();
+        }
+        if let Some(grace) = self.grace {
+            venue.grace = Duration::from_secs(grace);
+        }
+        posture.migrate().await?;
+        Host::new(posture).serve(&venue).await
+    }
+}
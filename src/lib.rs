@@ -1,12 +1,21 @@
 /// The `prelude` module contains re-exports of the primary structs and functions in the library
 /// for easier use.
 pub mod prelude {
-    pub use cordial_guest::Guest;
-    pub use cordial_host::Host;
-    pub use cordial_improv::{Improv, Pass};
-    pub use cordial_memory::Memorable;
-    pub use cordial_posture::Posture;
-    pub use cordial_recall::Recall;
-    pub use counsel::Counsel;
-    pub use polite::{FauxPas, Polite};
+    pub use cordial_concierge::{Concierge, Directions};
+    pub use cordial_guest::{Guest, Inquiry, Order, Sort};
+    pub use cordial_host::{
+        Allowance, Bounds, Courier, Credentials, Door, Doorman, Group, Host, Lockout, Quota, Rate,
+        Tally, Usher, Venue, Welcome,
+    };
+    pub use cordial_improv::{
+        Appraisal, Bulk, Candidate, Improv, Lexicon, Pass, Passphrase, Pattern, Phrase, Policy,
+        Vacancy,
+    };
+    pub use cordial_memory::{Memorable, Roster};
+    pub use cordial_posture::{Milestone, Posture};
+    pub use cordial_recall::{
+        Action, Audit, Ledger, Recall, Rehearsal, Review, Seeding, Vitals, Witness,
+    };
+    pub use counsel::{Attended, Counsel, Vet, Vetted};
+    pub use polite::{FauxPas, Flaw, Polite, Problem};
 }
@@ -0,0 +1,352 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use cordial::prelude::*;
+use http_body_util::BodyExt;
+use tokio::sync::oneshot;
+use tower::ServiceExt;
+use tracing::{info, trace};
+
+pub async fn audit(host: &Host) -> Polite<()> {
+    trail(host).await?;
+    append_only(host).await?;
+    attended(host).await?;
+    paging(host).await?;
+    ledgered(host).await?;
+    rehearsed(host).await?;
+    witnessed(host).await?;
+    freed(host).await?;
+    info!("Audit test successful.");
+    Ok(())
+}
+
+/// Returns the actions recorded for the guest with `id`, oldest first.
+async fn actions(recall: &Recall, id: uuid::Uuid) -> Polite<Vec<Action>> {
+    let mut review = Review::new();
+    review.guest_id = Some(id);
+    let mut audits = recall.audits(&review).await?;
+    audits.reverse();
+    Ok(audits.iter().map(|audit| audit.action).collect())
+}
+
+/// Checks that each change through the [`Recall`] is recorded with a diff of the fields changed,
+/// and that passwords never reach the trail.
+async fn trail(host: &Host) -> Polite<()> {
+    let recall = host.recall.attended(Witness::new("auditor-48"));
+    let mut improv = Improv::seeded(48);
+    let guest = improv.guest()?;
+    let created = recall.create(&guest).await?;
+    let mut changed = created.clone();
+    changed.hash = improv.pass()?;
+    let changed = recall.update(&changed).await?;
+    recall.record_login(guest.id).await?;
+    recall.delete(&changed).await?;
+    recall.delete(&changed).await?;
+    assert!(recall.record_login(guest.id).await.is_err());
+    recall.restore(guest.id).await?;
+    recall.delete(&changed).await?;
+    recall.purge(guest.id).await?;
+    assert_eq!(
+        actions(&recall, guest.id).await?,
+        [
+            Action::Create,
+            Action::Update,
+            Action::Login,
+            Action::Delete,
+            Action::LoginFailed,
+            Action::Restore,
+            Action::Delete,
+            Action::Purge,
+        ]
+    );
+
+    let mut review = Review::new();
+    review.guest_id = Some(guest.id);
+    review.action = Some(Action::Update);
+    let updates = recall.audits(&review).await?;
+    assert_eq!(updates.len(), 1);
+    let update = &updates[0];
+    trace!("{:#?}", update);
+    assert_eq!(update.actor, "auditor-48");
+    assert_eq!(update.request_id, None);
+    assert_eq!(update.diff["hash"]["before"], Audit::REDACTED);
+    assert_eq!(update.diff["hash"]["after"], Audit::REDACTED);
+    assert!(update.diff.get("name").is_none());
+    review.action = Some(Action::Create);
+    let create = &recall.audits(&review).await?[0];
+    assert_eq!(create.diff["name"]["before"], serde_json::Value::Null);
+    assert_eq!(create.diff["name"]["after"], guest.name.as_str());
+    assert_eq!(create.diff["hash"]["after"], Audit::REDACTED);
+    assert!(!create.diff.to_string().contains(&guest.hash));
+    review.action = Some(Action::LoginFailed);
+    assert!(recall.audits(&review).await?[0]
+        .diff
+        .as_object()
+        .is_some_and(|d| d.is_empty()));
+
+    let batch = improv.guests(3)?;
+    recall.create_many(&batch).await?;
+    recall.delete_many(&batch).await?;
+    for guest in &batch {
+        assert_eq!(
+            actions(&recall, guest.id).await?,
+            [Action::Create, Action::Delete]
+        );
+        recall.purge(guest.id).await?;
+    }
+    assert_eq!(host.recall.witness.actor, Witness::SYSTEM);
+    Ok(())
+}
+
+/// Checks that the database refuses to change or remove an entry.
+async fn append_only(host: &Host) -> Polite<()> {
+    let changed = sqlx::query("UPDATE audits SET actor = 'mallory'")
+        .execute(&host.recall.book)
+        .await;
+    assert!(changed.is_err());
+    let removed = sqlx::query("DELETE FROM audits")
+        .execute(&host.recall.book)
+        .await;
+    assert!(removed.is_err());
+    Ok(())
+}
+
+/// Checks that a change made over HTTP records the caller and the request id.
+async fn attended(host: &Host) -> Polite<()> {
+    let guest = Improv::seeded(148).guest()?;
+    let response = host
+        .bearing()
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/guests")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-actor", "clerk-48")
+                .header("x-request-id", "errand-48")
+                .body(Body::from(serde_json::to_vec(&guest)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let guest: Guest = serde_json::from_slice(&body)?;
+
+    let response = host
+        .bearing()
+        .oneshot(
+            Request::builder()
+                .uri("/admin/audits?actor=clerk-48&request_id=errand-48")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let audits: Vec<Audit> = serde_json::from_slice(&body)?;
+    assert_eq!(audits.len(), 1);
+    assert_eq!(audits[0].guest_id, guest.id);
+    assert_eq!(audits[0].action, Action::Create);
+    assert_eq!(audits[0].ip, None);
+
+    let response = host
+        .bearing()
+        .oneshot(
+            Request::builder()
+                .uri("/admin/audits?limit=0")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body)?.flaws;
+    assert_eq!(flaws[0].field, "limit");
+    host.recall.delete(&guest).await?;
+    Ok(())
+}
+
+/// Checks that pages follow one another without gaps or repeats.
+async fn paging(host: &Host) -> Polite<()> {
+    let recall = host.recall.attended(Witness::new("pager-48"));
+    let batch = Improv::seeded(248).guests(5)?;
+    recall.create_many(&batch).await?;
+    let mut review = Review::new();
+    review.actor = Some("pager-48".to_string());
+    review.limit = Some(2);
+    let mut pages = Vec::new();
+    loop {
+        let page = recall.audits(&review).await?;
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 2);
+        review.cursor = page.last().map(|audit| audit.id);
+        pages.push(page);
+    }
+    assert_eq!(pages.len(), 3);
+    let ids = pages.iter().flatten().map(|a| a.id).collect::<Vec<_>>();
+    assert!(ids.windows(2).all(|pair| pair[0] > pair[1]));
+    let mut guests = pages
+        .iter()
+        .flatten()
+        .map(|a| a.guest_id)
+        .collect::<Vec<_>>();
+    guests.reverse();
+    assert_eq!(guests, batch.iter().map(|g| g.id).collect::<Vec<_>>());
+
+    review.cursor = None;
+    review.limit = None;
+    review.since = Some(chrono::Utc::now());
+    assert!(recall.audits(&review).await?.is_empty());
+    recall.delete_many(&batch).await?;
+    Ok(())
+}
+
+/// Returns the actions listed at `/admin/audits` for the guest with `id`, oldest first.
+async fn listed(host: &Host, id: uuid::Uuid) -> Polite<Vec<(String, Action)>> {
+    let response = host
+        .bearing()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/admin/audits?guest_id={}", id))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let audits: Vec<Audit> = serde_json::from_slice(&body)?;
+    Ok(audits
+        .into_iter()
+        .rev()
+        .map(|audit| (audit.actor, audit.action))
+        .collect())
+}
+
+/// Checks that changes made through a [`Ledger`] are recorded once committed, and that changes
+/// rolled back leave no trace.
+async fn ledgered(host: &Host) -> Polite<()> {
+    let recall = host.recall.attended(Witness::new("ledger-48"));
+    let mut improv = Improv::seeded(448);
+    let guest = improv.guest()?;
+    let kept = improv.guests(2)?;
+    let dropped = improv.guest()?;
+    let ledger = recall.ledger().await?;
+    let mut changed = ledger.create(&guest).await?;
+    changed.name = improv.name()?;
+    ledger.update(&changed).await?;
+    ledger.create_many(&kept).await?;
+    ledger.delete_many(&kept).await?;
+    let savepoint = ledger.savepoint().await?;
+    savepoint.create(&dropped).await?;
+    savepoint.rollback().await?;
+    assert!(listed(host, guest.id).await?.is_empty());
+    ledger.commit().await?;
+
+    let by = |action| ("ledger-48".to_string(), action);
+    assert_eq!(
+        listed(host, guest.id).await?,
+        [by(Action::Create), by(Action::Update)]
+    );
+    for guest in &kept {
+        assert_eq!(
+            listed(host, guest.id).await?,
+            [by(Action::Create), by(Action::Delete)]
+        );
+        recall.purge(guest.id).await?;
+    }
+    assert!(listed(host, dropped.id).await?.is_empty());
+    recall.delete(&changed).await?;
+    recall.purge(guest.id).await?;
+    Ok(())
+}
+
+/// Checks that seeding records each guest planted, and that a reset records the guests it
+/// erases as purged.
+async fn rehearsed(host: &Host) -> Polite<()> {
+    let recall = host.recall.attended(Witness::new("rehearsal-48"));
+    let rehearsal = Rehearsal::new(548, 2);
+    rehearsal.seed(&recall).await?;
+    rehearsal.reset(&recall).await?;
+    let by = |action| ("rehearsal-48".to_string(), action);
+    for guest in rehearsal.fixtures()? {
+        assert_eq!(
+            listed(host, guest.id).await?,
+            [by(Action::Create), by(Action::Purge), by(Action::Create)]
+        );
+        recall.delete(&guest).await?;
+        recall.purge(guest.id).await?;
+    }
+    Ok(())
+}
+
+/// Checks that a served host records the address of the client, and that the [`Concierge`] names
+/// its caller.
+async fn witnessed(host: &Host) -> Polite<()> {
+    let served = Host::new(host.posture.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        served
+            .serve_until(listener, &Venue::new(), async {
+                stopped.await.ok();
+            })
+            .await
+    });
+    let mut directions = Directions::new(&format!("http://{}", addr));
+    directions.actor = Some("concierge-48".to_string());
+    let result = async {
+        let concierge = Concierge::new(directions)?;
+        let guest = Improv::seeded(348).guest()?;
+        let guest = concierge.check_in(&guest).await?;
+        concierge.check_out(&guest).await?;
+        let mut review = Review::new();
+        review.guest_id = Some(guest.id);
+        review.action = Some(Action::Delete);
+        let audits = concierge.audits(&review).await?;
+        review.limit = Some(501);
+        let refused = concierge.audits(&review).await;
+        Ok::<_, FauxPas>((audits, refused))
+    }
+    .await;
+    stop.send(()).ok();
+    server.await.map_err(std::io::Error::other)??;
+    let (audits, refused) = result?;
+    assert_eq!(audits.len(), 1);
+    assert_eq!(audits[0].actor, "concierge-48");
+    assert_eq!(audits[0].ip.as_deref(), Some("127.0.0.1"));
+    assert!(audits[0].request_id.is_some());
+    assert!(matches!(refused, Err(FauxPas::Invalid(_))));
+    Ok(())
+}
+
+/// Checks that taking the name of an expired guest, alone or in a batch, erases them with a
+/// purge in the trail.
+async fn freed(host: &Host) -> Polite<()> {
+    let mut recall = host.recall.attended(Witness::new("registrar-48"));
+    recall.retention = std::time::Duration::ZERO;
+    let mut improv = Improv::seeded(348);
+    let single = recall.create(&improv.guest()?).await?;
+    let batched = recall.create(&improv.guest()?).await?;
+    recall
+        .delete_many(&[single.clone(), batched.clone()])
+        .await?;
+
+    let mut namesake = improv.guest()?;
+    namesake.name = single.name.clone();
+    let created = recall.create(&namesake).await?;
+    let mut namesakes = improv.guests(2)?;
+    namesakes[1].name = batched.name.clone();
+    let batch = recall.create_many(&namesakes).await?;
+    for guest in [&single, &batched] {
+        assert_eq!(
+            actions(&recall, guest.id).await?,
+            [Action::Create, Action::Delete, Action::Purge]
+        );
+    }
+    let mut review = Review::new();
+    review.guest_id = Some(single.id);
+    review.action = Some(Action::Purge);
+    let purges = recall.audits(&review).await?;
+    assert_eq!(purges[0].actor, "registrar-48");
+    assert!(recall.get(single.id).await.is_err());
+    recall.delete(&created).await?;
+    recall.delete_many(&batch).await?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use cordial::prelude::*;
+use std::process::{Command, Output};
+use tracing::info;
+
+fn cordial(args: &[&str]) -> Polite<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_cordial"))
+        .args(args)
+        .env("RUST_LOG", "off")
+        .output()?)
+}
+
+fn json<T: serde::de::DeserializeOwned>(args: &[&str]) -> Polite<T> {
+    let output = cordial(&[args, &["--format", "json"]].concat())?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+pub async fn command_line(host: &Host) -> Polite<()> {
+    info!("Testing command line.");
+    let status: serde_json::Value = json(&["db", "status"])?;
+    assert_eq!(status["exists"], true);
+    assert!(status["migrations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|milestone| milestone["applied"] == true));
+
+    let name = Improv::default().name()?;
+    let added: Guest = json(&["guest", "add", &name])?;
+    assert_eq!(added.name, name);
+    Policy::default().check("pass", &name, &added.hash)?;
+    let shown: Guest = json(&["guest", "show", &name.to_uppercase()])?;
+    assert_eq!(added, shown);
+
+    let renamed = Improv::default().name()?;
+    let id = added.id.to_string();
+    let guest: Guest = json(&["guest", "rename", &id, &renamed])?;
+    assert_eq!(guest.name, renamed);
+    let guest: Guest = json(&[
+        "guest",
+        "reset-password",
+        &renamed,
+        "--pass",
+        "Tr0ub4dor&3x!",
+    ])?;
+    assert_eq!(host.recall.get(added.id).await?.hash, "Tr0ub4dor&3x!");
+    assert_eq!(guest.hash, "Tr0ub4dor&3x!");
+
+    let weak = cordial(&["guest", "reset-password", &renamed, "--pass", "password"])?;
+    assert!(!weak.status.success());
+    assert!(String::from_utf8_lossy(&weak.stderr).contains("commonly used"));
+
+    let listed = cordial(&["guest", "list", "--sort", "name"])?;
+    let table = String::from_utf8_lossy(&listed.stdout);
+    assert!(table.starts_with("ID "));
+    assert!(table.contains(&renamed));
+
+    let removed: Guest = json(&["guest", "remove", &id])?;
+    assert!(removed.deleted_at.is_some());
+    let deleted: Vec<Guest> = json(&["guest", "list", "--deleted"])?;
+    assert!(deleted.iter().any(|guest| guest.id == added.id));
+
+    host.recall.restore(added.id).await?;
+    let _: Guest = json(&["guest", "remove", &renamed, "--purge"])?;
+    assert!(host
+        .recall
+        .deleted()
+        .await?
+        .iter()
+        .all(|guest| guest.id != added.id));
+    info!("Command line successful.");
+    Ok(())
+}
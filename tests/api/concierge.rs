@@ -0,0 +1,145 @@
+use cordial::prelude::*;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{info, trace};
+
+pub async fn concierge(host: &Host) -> Polite<()> {
+    let served = Host::new(host.posture.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        served
+            .serve_until(listener, &Venue::new(), async {
+                stopped.await.ok();
+            })
+            .await
+    });
+    let result = errands(&format!("http://{}", addr)).await;
+    stop.send(()).ok();
+    server.await.map_err(std::io::Error::other)??;
+    result?;
+    unreachable().await?;
+    info!("Concierge test successful.");
+    Ok(())
+}
+
+async fn errands(base: &str) -> Polite<()> {
+    let concierge = Concierge::new(Directions::new(base))?;
+    concierge.live().await?;
+    let vitals = concierge.ready().await?;
+    assert!(vitals.ready);
+    assert!(concierge.book().await?.starts_with("PostgreSQL"));
+    let charter = concierge.openapi().await?;
+    assert!(charter["paths"].get("/guests").is_some());
+    assert!(concierge.metrics().await?.contains(Tally::REQUESTS));
+
+    let mut improv = Improv::seeded(44);
+    let guest = improv.guest()?;
+    let created = concierge.check_in(&guest).await?;
+    assert_ne!(created.id, guest.id);
+    let guest = Guest {
+        id: created.id,
+        ..guest
+    };
+    assert_eq!(concierge.guest(guest.id).await?.name, guest.name);
+    let named = concierge.guest_named(&guest.name.to_uppercase()).await?;
+    assert_eq!(named.id, guest.id);
+    let mut inquiry = Inquiry::new();
+    inquiry.sort = Some(Sort::Name);
+    inquiry.created_after = Some(created.created_at);
+    let listed = concierge.guests(&inquiry).await?;
+    assert!(listed.iter().any(|g| g.id == guest.id));
+
+    let mut renamed = created.clone();
+    renamed.name = format!("{} a/b ?c", renamed.name);
+    let updated = concierge.update(&renamed).await?;
+    assert_eq!(updated.name, renamed.name);
+    assert_eq!(concierge.guest_named(&renamed.name).await?.id, guest.id);
+
+    let mut weak = improv.guest()?;
+    weak.hash = "password".to_string();
+    match concierge.check_in(&weak).await {
+        Err(FauxPas::Invalid(flaws)) => {
+            assert!(flaws.iter().all(|flaw| flaw.field == "hash"));
+            trace!("Weak pass refused: {:?}", flaws);
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    match concierge.guest(uuid::Uuid::new_v4()).await {
+        Err(FauxPas::Status { status, .. }) => assert_eq!(status, 400),
+        _ => return Err(FauxPas::BadTest),
+    }
+
+    concierge.check_out(&updated).await?;
+    assert!(concierge.departed().await?.iter().any(|g| g.id == guest.id));
+    assert_eq!(concierge.restore(guest.id).await?.id, guest.id);
+    concierge.check_out(&updated).await?;
+    concierge.purge(guest.id).await?;
+    assert!(concierge.guest(guest.id).await.is_err());
+    concierge.sweep().await?;
+
+    let batch = improv.guests(3)?;
+    let created = concierge.check_in_batch(&batch).await?;
+    assert_eq!(created.len(), 3);
+    let updated = concierge.update_batch(&created).await?;
+    assert_eq!(updated.len(), 3);
+    concierge.check_out_batch(&updated).await?;
+
+    assert!(!concierge.name().await?.is_empty());
+    assert!(concierge
+        .numbered_name()
+        .await?
+        .ends_with(|c: char| c.is_ascii_digit()));
+    assert_eq!(concierge.vacancy(&Vacancy::default()).await?.len(), 1);
+    let vacancy = Vacancy {
+        count: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(concierge.vacancy(&vacancy).await?.len(), 3);
+    assert!(!concierge.pass().await?.is_empty());
+    let mut pass = Pass {
+        length: 24,
+        ..Default::default()
+    };
+    assert_eq!(concierge.pass_with(&pass).await?.chars().count(), 24);
+    let bulk = Bulk {
+        count: 4,
+        seed: Some(7),
+    };
+    let names = concierge.names(&bulk).await?;
+    assert_eq!(names, concierge.names(&bulk).await?);
+    assert_eq!(concierge.passes(&bulk, &pass).await?.len(), 4);
+    assert_eq!(concierge.improvise(&bulk).await?.len(), 4);
+    let phrase = concierge.passphrase(&Passphrase::default()).await?;
+    assert!(phrase.entropy > 0.0);
+    let appraisal = concierge
+        .score(&Candidate {
+            pass: "password".to_string(),
+            name: None,
+        })
+        .await?;
+    assert!(!appraisal.flaws.is_empty());
+    pass.length = 0;
+    match concierge.pass_with(&pass).await {
+        Err(FauxPas::Invalid(flaws)) => assert_eq!(flaws[0].field, "length"),
+        _ => return Err(FauxPas::BadTest),
+    }
+    Ok(())
+}
+
+/// Checks that a `Concierge` retries a server that cannot be reached, then gives up.
+async fn unreachable() -> Polite<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    drop(listener);
+    let mut directions = Directions::new(&format!("http://{}/", addr));
+    directions.retries = 2;
+    directions.backoff = Duration::from_millis(50);
+    let concierge = Concierge::new(directions)?;
+    let started = std::time::Instant::now();
+    assert!(matches!(concierge.live().await, Err(FauxPas::Http(_))));
+    assert!(started.elapsed() >= Duration::from_millis(150));
+    assert!(Concierge::new(Directions::new("mailto:host@example.com")).is_err());
+    Ok(())
+}
@@ -5,17 +5,14 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::test]
 pub async fn conduct() -> Polite<()> {
-    match tracing_subscriber::registry()
+    tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "cordial=info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
         .try_init()
-    {
-        Ok(_) => {}
-        Err(_) => {}
-    };
+        .ok();
     trace!("Subscriber initialized.");
     let mut host = Host::from_env().await?;
     info!("Host created.");
@@ -25,11 +22,56 @@ pub async fn conduct() -> Polite<()> {
     info!("Guest check successful.");
     guest_lifecycle(&mut host).await?;
     info!("Guest lifecycle successful.");
+    guest_timestamps(&host).await?;
+    info!("Guest timestamps successful.");
+    guest_archive(&host).await?;
+    info!("Guest archive successful.");
+    guest_batch(&host).await?;
+    info!("Guest batch successful.");
+    guest_ledger(&host).await?;
+    info!("Guest ledger successful.");
+    guest_names(&host).await?;
+    info!("Guest names successful.");
+    guest_folding(&host).await?;
+    info!("Guest folding successful.");
+    guest_policy(&host).await?;
+    info!("Guest policy successful.");
+    guest_vetting(&host).await?;
+    info!("Guest vetting successful.");
+    guest_seeding(&host).await?;
+    info!("Guest seeding successful.");
+    command_line(&host).await?;
+    info!("Command line successful.");
+    serving(&host).await?;
+    info!("Serving successful.");
+    welcoming(&host).await?;
+    info!("Welcoming successful.");
+    vitals(&host).await?;
+    info!("Vitals successful.");
+    charter(&host).await?;
+    info!("Charter successful.");
+    escorting(&host).await?;
+    info!("Escorting successful.");
+    tally(&host).await?;
+    info!("Tally successful.");
+    courier(&host).await?;
+    info!("Courier successful.");
+    concierge(&host).await?;
+    info!("Concierge successful.");
+    audit(&host).await?;
+    info!("Audit successful.");
+    door(&host).await?;
+    info!("Door successful.");
+    bounding(&host).await?;
+    info!("Bounding successful.");
 
     info!("Checking improvisation.");
     improvise(&host).await?;
     info!("Improvisation successful.");
 
+    seeded()?;
+    info!("Seeded improvisation successful.");
+
     fauxpas()?;
     info!("Fauxpas test successful.");
 
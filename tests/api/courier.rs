@@ -0,0 +1,233 @@
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, Request};
+use axum::routing::post;
+use axum::Router;
+use cordial::prelude::*;
+use http_body_util::BodyExt;
+use opentelemetry::trace::{SpanId, SpanKind, TraceId};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+use tracing::{info, trace};
+
+/// The trace id sent in the `traceparent` header.
+const TRACE: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+/// The span id of the caller sent in the `traceparent` header.
+const PARENT: &str = "00f067aa0ba902b7";
+
+pub async fn courier(host: &Host) -> Polite<()> {
+    settings()?;
+    spans(host).await?;
+    operations(host).await?;
+    export(host).await?;
+    info!("Courier test successful.");
+    Ok(())
+}
+
+fn settings() -> Polite<()> {
+    assert!(Courier::new().dispatch()?.is_none());
+    let courier = Courier {
+        enabled: true,
+        endpoint: "127.0.0.1:4318".to_string(),
+        service: " ".to_string(),
+        ..Default::default()
+    };
+    match courier.dispatch() {
+        Err(FauxPas::Invalid(flaws)) => {
+            let fields = flaws.iter().map(|f| f.field.as_str()).collect::<Vec<_>>();
+            assert_eq!(fields, ["endpoint", "service"]);
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    Ok(())
+}
+
+/// Checks that a request continues the trace named in its `traceparent` header, and that the
+/// database call it makes is a child span carrying the database attributes.
+async fn spans(host: &Host) -> Polite<()> {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let guest = Improv::seeded(47).guest()?;
+    host.recall.create(&guest).await?;
+    let (status, headers) = {
+        let _guard =
+            tracing::subscriber::set_default(Usher::subscriber("cordial=info", Some(&provider)));
+        let response = host
+            .bearing()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/guests/{}", guest.id))
+                    .header("traceparent", format!("00-{}-{}-01", TRACE, PARENT))
+                    .body(Body::empty())?,
+            )
+            .await?;
+        // The request span closes once the body is read.
+        let (parts, body) = response.into_parts();
+        body.collect().await?;
+        (parts.status, parts.headers)
+    };
+    host.recall.delete(&guest).await?;
+    assert_eq!(status, 200);
+    let traceparent = headers.get("traceparent").ok_or(FauxPas::BadTest)?;
+    assert!(String::from_utf8_lossy(traceparent.as_bytes()).starts_with(&format!("00-{}-", TRACE)));
+    assert!(!headers.contains_key("tracestate"));
+
+    let spans = exporter
+        .get_finished_spans()
+        .map_err(std::io::Error::other)?;
+    let request = spans
+        .iter()
+        .find(|span| span.name == "GET /guests/:id")
+        .ok_or(FauxPas::BadTest)?;
+    assert_eq!(request.span_kind, SpanKind::Server);
+    assert_eq!(request.span_context.trace_id(), TraceId::from_hex(TRACE)?);
+    assert_eq!(request.parent_span_id, SpanId::from_hex(PARENT)?);
+    let query = spans
+        .iter()
+        .find(|span| span.name == "get")
+        .ok_or(FauxPas::BadTest)?;
+    trace!("{:#?}", query);
+    assert_eq!(query.span_kind, SpanKind::Client);
+    assert_eq!(query.span_context.trace_id(), TraceId::from_hex(TRACE)?);
+    assert_eq!(query.parent_span_id, request.span_context.span_id());
+    let attribute = |key: &str| {
+        query
+            .attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+    };
+    assert_eq!(attribute("db.system").as_deref(), Some("postgresql"));
+    assert_eq!(attribute("db.operation").as_deref(), Some("get"));
+    assert!(attribute("db.statement").is_some_and(|sql| sql.starts_with("SELECT")));
+    Ok(())
+}
+
+/// Checks that every database operation, pooled or inside a [`Ledger`], runs in its own `query`
+/// span carrying the database attributes and the statement it ran.
+async fn operations(host: &Host) -> Polite<()> {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let guest = Improv::seeded(147).guest()?;
+    {
+        let _guard =
+            tracing::subscriber::set_default(Usher::subscriber("cordial=info", Some(&provider)));
+        let ledger = host.recall.ledger().await?;
+        ledger.create(&guest).await?;
+        ledger.get(guest.id).await?;
+        ledger.commit().await?;
+        host.recall.get_by_name(&guest.name).await?;
+        host.recall.exists_by_name(&guest.name).await?;
+        host.recall
+            .vacant(std::slice::from_ref(&guest.name))
+            .await?;
+        host.recall.inquire(&Inquiry::default()).await?;
+        host.recall.record_login(guest.id).await?;
+        host.recall.audits(&Review::new()).await?;
+        host.recall.delete(&guest).await?;
+        host.recall.deleted().await?;
+        host.recall.restore(guest.id).await?;
+        host.recall.delete(&guest).await?;
+        host.recall.purge(guest.id).await?;
+        host.recall.sweep().await?;
+    }
+    let spans = exporter
+        .get_finished_spans()
+        .map_err(std::io::Error::other)?;
+    for operation in [
+        "ledger",
+        "create",
+        "get",
+        "commit",
+        "get_by_name",
+        "exists_by_name",
+        "vacant",
+        "inquire",
+        "record_login",
+        "audits",
+        "delete",
+        "deleted",
+        "restore",
+        "purge",
+        "sweep",
+    ] {
+        let query = spans
+            .iter()
+            .find(|span| span.name == operation)
+            .unwrap_or_else(|| panic!("No span for {}.", operation));
+        let attribute = |key: &str| {
+            query
+                .attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.to_string())
+        };
+        assert_eq!(query.span_kind, SpanKind::Client, "{}", operation);
+        assert_eq!(attribute("db.system").as_deref(), Some("postgresql"));
+        assert_eq!(attribute("db.operation").as_deref(), Some(operation));
+        assert!(
+            attribute("db.statement").is_some_and(|sql| !sql.is_empty()),
+            "No statement for {}.",
+            operation
+        );
+    }
+    trace!("Checked {} spans.", spans.len());
+    Ok(())
+}
+
+/// Checks that spans reach a collector over OTLP when export is enabled.
+async fn export(host: &Host) -> Polite<()> {
+    let (sent, mut received) = mpsc::unbounded_channel::<(HeaderMap, Bytes)>();
+    let collector = Router::new().route(
+        Courier::TRACES,
+        post(move |headers: HeaderMap, body: Bytes| async move {
+            sent.send((headers, body)).ok();
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = tokio::spawn(async move { axum::serve(listener, collector).await });
+
+    let courier = Courier {
+        enabled: true,
+        endpoint: format!("http://{}/", addr),
+        service: "cordial-courier".to_string(),
+        ..Default::default()
+    };
+    let provider = courier.dispatch()?.ok_or(FauxPas::BadTest)?;
+    let status = {
+        let _guard =
+            tracing::subscriber::set_default(Usher::subscriber("cordial=info", Some(&provider)));
+        let response = host
+            .bearing()
+            .oneshot(Request::builder().uri("/health/live").body(Body::empty())?)
+            .await?;
+        let status = response.status();
+        response.into_body().collect().await?;
+        status
+    };
+    assert_eq!(status, 200);
+    tokio::task::spawn_blocking(move || provider.shutdown())
+        .await
+        .map_err(std::io::Error::other)?
+        .map_err(std::io::Error::other)?;
+    let (headers, body) = tokio::time::timeout(Duration::from_secs(5), received.recv())
+        .await
+        .map_err(std::io::Error::other)?
+        .ok_or(FauxPas::BadTest)?;
+    server.abort();
+    assert_eq!(
+        headers.get(header::CONTENT_TYPE).map(|v| v.as_bytes()),
+        Some(&b"application/x-protobuf"[..])
+    );
+    assert!(body
+        .windows(b"cordial-courier".len())
+        .any(|w| w == b"cordial-courier"));
+    trace!("Collector received {} bytes.", body.len());
+    Ok(())
+}
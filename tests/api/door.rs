@@ -0,0 +1,263 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, Response};
+use axum::Router;
+use cordial::prelude::*;
+use http_body_util::BodyExt;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tower::ServiceExt;
+use tracing::{info, trace};
+
+pub async fn door(host: &Host) -> Polite<()> {
+    settings()?;
+    accounts(host).await?;
+    addresses(host).await?;
+    lockout()?;
+    login(host).await?;
+    info!("Door test successful.");
+    Ok(())
+}
+
+fn settings() -> Polite<()> {
+    assert_eq!(
+        Rate::parse("rate", "30/60")?,
+        Some(Rate::new(30, Duration::from_secs(60)))
+    );
+    assert_eq!(Rate::parse("rate", " off ")?, None);
+    match Rate::parse("rate", "30 per minute") {
+        Err(FauxPas::Invalid(flaws)) => assert_eq!(flaws[0].field, "rate"),
+        _ => return Err(FauxPas::BadTest),
+    }
+    assert_eq!(Group::of("/improv/pass"), Some(Group::Improv));
+    assert_eq!(Group::of("/admin/audits"), Some(Group::Admin));
+    assert_eq!(Group::of("/health/live"), Some(Group::Health));
+    assert_eq!(Group::of("/nowhere"), None);
+    let doorman = Doorman::new();
+    assert!(doorman.allowance(Group::Health).ip.is_none());
+    assert_eq!(doorman.allowance(Group::Improv).ip, Some(Doorman::IMPROV));
+    assert_eq!(doorman.allowance(Group::Improv).account, None);
+
+    let mut doorman = Doorman::new();
+    doorman.groups.insert(
+        Group::Improv,
+        Allowance {
+            ip: Some(Rate::new(0, Duration::from_secs(60))),
+            account: None,
+        },
+    );
+    doorman.lockout.max = Duration::ZERO;
+    match doorman.validate() {
+        Err(FauxPas::Invalid(flaws)) => {
+            let fields = flaws.iter().map(|f| f.field.as_str()).collect::<Vec<_>>();
+            assert_eq!(fields, ["groups.improv.ip", "lockout.max"]);
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    Ok(())
+}
+
+/// Sends a `GET` for `uri` on behalf of `actor`.
+async fn knock(router: Router, uri: &str, actor: &str) -> Polite<Response<Body>> {
+    Ok(router
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header("x-actor", actor)
+                .body(Body::empty())?,
+        )
+        .await?)
+}
+
+/// Reads the header `name` of `response` as a number.
+fn number<B>(response: &Response<B>, name: &str) -> Polite<u64> {
+    let value = response.headers().get(name).ok_or(FauxPas::BadTest)?;
+    Ok(String::from_utf8_lossy(value.as_bytes()).parse()?)
+}
+
+/// Checks that each caller named in `x-actor` has their own bucket in each group.
+async fn accounts(host: &Host) -> Polite<()> {
+    let mut doorman = Doorman::new();
+    doorman.groups.insert(
+        Group::Improv,
+        Allowance {
+            ip: None,
+            account: Some(Rate::new(2, Duration::from_secs(60))),
+        },
+    );
+    let router = host.guarded(&doorman)?.bearing();
+    let first = knock(router.clone(), "/improv/name/num", "knocker-49").await?;
+    assert_eq!(first.status(), 200);
+    assert_eq!(number(&first, "ratelimit-limit")?, 2);
+    assert_eq!(number(&first, "ratelimit-remaining")?, 1);
+    assert!(number(&first, "ratelimit-reset")? <= 30);
+    let second = knock(router.clone(), "/improv/name/num", "knocker-49").await?;
+    assert_eq!(second.status(), 200);
+    assert_eq!(number(&second, "ratelimit-remaining")?, 0);
+    let third = knock(router.clone(), "/improv/name/num", "knocker-49").await?;
+    assert_eq!(third.status(), 429);
+    let wait = number(&third, header::RETRY_AFTER.as_str())?;
+    assert!((1..=30).contains(&wait));
+    assert_eq!(number(&third, "ratelimit-remaining")?, 0);
+    assert_eq!(third.headers()[header::CONTENT_TYPE], Problem::CONTENT_TYPE);
+    let request_id = third.headers()["x-request-id"]
+        .to_str()
+        .map_err(|_| FauxPas::BadTest)?
+        .to_string();
+    let body = third.into_body().collect().await?.to_bytes();
+    let problem = serde_json::from_slice::<Problem>(&body)?;
+    assert_eq!(problem.status, 429);
+    assert_eq!(problem.request_id, Some(request_id));
+    trace!("Retry after {} seconds.", wait);
+
+    let other = knock(router.clone(), "/improv/name/num", "other-49").await?;
+    assert_eq!(other.status(), 200);
+    let health = knock(router.clone(), "/health/live", "knocker-49").await?;
+    assert_eq!(health.status(), 200);
+    assert!(!health.headers().contains_key("ratelimit-limit"));
+
+    doorman.enabled = false;
+    let router = host.guarded(&doorman)?.bearing();
+    let open = knock(router, "/improv/name/num", "knocker-49").await?;
+    assert_eq!(open.status(), 200);
+    assert!(!open.headers().contains_key("ratelimit-limit"));
+    Ok(())
+}
+
+/// Checks that a served host limits each client address.
+async fn addresses(host: &Host) -> Polite<()> {
+    let served = Host::new(host.posture.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (stop, stopped) = oneshot::channel::<()>();
+    let mut venue = Venue::new();
+    venue.doorman.groups.insert(
+        Group::Improv,
+        Allowance {
+            ip: Some(Rate::new(1, Duration::from_secs(60))),
+            account: None,
+        },
+    );
+    let server = tokio::spawn(async move {
+        served
+            .serve_until(listener, &venue, async {
+                stopped.await.ok();
+            })
+            .await
+    });
+    let url = format!("http://{}/improv/pass", addr);
+    let result = async {
+        let first = reqwest::get(&url).await.map_err(std::io::Error::other)?;
+        let second = reqwest::get(&url).await.map_err(std::io::Error::other)?;
+        Ok::<_, FauxPas>((first.status(), second.status(), second.headers().clone()))
+    }
+    .await;
+    stop.send(()).ok();
+    server.await.map_err(std::io::Error::other)??;
+    let (first, second, headers) = result?;
+    assert_eq!(first, 200);
+    assert_eq!(second, 429);
+    assert!(headers.contains_key(reqwest::header::RETRY_AFTER));
+    Ok(())
+}
+
+/// Checks that repeated failed logins lock a guest out for doubling periods.
+fn lockout() -> Polite<()> {
+    let mut doorman = Doorman::new();
+    doorman.lockout = Lockout {
+        threshold: 3,
+        base: Duration::from_secs(10),
+        max: Duration::from_secs(30),
+    };
+    assert_eq!(doorman.lockout.penalty(2), None);
+    assert_eq!(doorman.lockout.penalty(4), Some(Duration::from_secs(20)));
+    assert_eq!(doorman.lockout.penalty(40), Some(Duration::from_secs(30)));
+
+    let door = Door::new(doorman);
+    let guest = Improv::seeded(49).guest()?;
+    assert_eq!(door.strike(guest.id), None);
+    assert_eq!(door.strike(guest.id), None);
+    assert_eq!(door.locked(guest.id), None);
+    assert_eq!(door.strike(guest.id), Some(Duration::from_secs(10)));
+    assert!(door.locked(guest.id).is_some());
+    assert_eq!(door.strike(guest.id), Some(Duration::from_secs(20)));
+    assert_eq!(door.strike(guest.id), Some(Duration::from_secs(30)));
+    assert_eq!(door.strike(guest.id), Some(Duration::from_secs(30)));
+    door.clear(guest.id);
+    assert_eq!(door.locked(guest.id), None);
+    assert_eq!(door.strike(guest.id), None);
+
+    let refused = Door::turn_away(Duration::from_millis(1500));
+    assert_eq!(refused.status(), 429);
+    assert_eq!(number(&refused, header::RETRY_AFTER.as_str())?, 2);
+    Ok(())
+}
+
+/// Sends a `POST` to `/guests/login` with the `name` and `hash` of a guest.
+async fn log_in(router: Router, name: &str, hash: &str) -> Polite<Response<Body>> {
+    let credentials = Credentials {
+        name: name.to_owned(),
+        hash: hash.to_owned(),
+    };
+    Ok(router
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/guests/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&credentials)?))?,
+        )
+        .await?)
+}
+
+/// Checks that failed logins lock a guest out of the login route, and that a successful login
+/// clears the count.
+async fn login(host: &Host) -> Polite<()> {
+    let guest = host.recall.create(&Improv::seeded(249).guest()?).await?;
+    let mut doorman = Doorman::new();
+    doorman.lockout = Lockout {
+        threshold: 2,
+        base: Duration::from_secs(60),
+        max: Duration::from_secs(60),
+    };
+    let router = host.guarded(&doorman)?.bearing();
+
+    let stranger = log_in(router.clone(), "nobody-49", &guest.hash).await?;
+    assert_eq!(stranger.status(), 401);
+    let wrong = log_in(router.clone(), &guest.name, "wrong-49").await?;
+    assert_eq!(wrong.status(), 401);
+    let body = wrong.into_body().collect().await?.to_bytes();
+    assert_eq!(serde_json::from_slice::<Problem>(&body)?.status, 401);
+    let right = log_in(router.clone(), &guest.name, &guest.hash).await?;
+    assert_eq!(right.status(), 200);
+    let body = right.into_body().collect().await?.to_bytes();
+    let entered: Guest = serde_json::from_slice(&body)?;
+    assert_eq!(entered.id, guest.id);
+    assert!(entered.last_login_at.is_some());
+
+    let cleared = log_in(router.clone(), &guest.name, "wrong-49").await?;
+    assert_eq!(cleared.status(), 401);
+    let locked = log_in(router.clone(), &guest.name, "wrong-49").await?;
+    assert_eq!(locked.status(), 429);
+    assert_eq!(number(&locked, header::RETRY_AFTER.as_str())?, 60);
+    assert_eq!(
+        locked.headers()[header::CONTENT_TYPE],
+        Problem::CONTENT_TYPE
+    );
+    let held = log_in(router.clone(), &guest.name, &guest.hash).await?;
+    assert_eq!(held.status(), 429);
+    assert!((1..=60).contains(&number(&held, header::RETRY_AFTER.as_str())?));
+
+    let audits = host
+        .recall
+        .audits(&Review {
+            guest_id: Some(guest.id),
+            ..Default::default()
+        })
+        .await?;
+    let refused = audits
+        .iter()
+        .filter(|audit| audit.action == Action::LoginFailed)
+        .count();
+    assert_eq!(refused, 4);
+    Ok(())
+}
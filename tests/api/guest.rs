@@ -1,5 +1,8 @@
 use axum::body::Body;
+use axum::extract::State;
 use axum::http::{self, Request};
+use axum::routing::post;
+use axum::{Json, Router};
 use cordial::prelude::*;
 use http_body_util::BodyExt;
 // use tokio::net::TcpListener;
@@ -7,31 +10,39 @@ use http_body_util::BodyExt;
 use tower::ServiceExt;
 use tracing::{info, trace};
 
+/// Asserts that two guests share the same identity and credentials, ignoring the timestamps set by
+/// the database.
+pub fn same(left: &Guest, right: &Guest) {
+    assert_eq!(left.id, right.id);
+    assert_eq!(left.name, right.name);
+    assert_eq!(left.hash, right.hash);
+}
+
 pub async fn booking(host: &Host) -> Polite<()> {
-    let mut improv = Improv::default();
+    let mut improv = Improv::seeded(21);
     let mut guest = improv.guest()?;
     info!("Guest: {:#?}", &guest);
     let created = host.recall.create(&guest).await?;
-    assert_eq!(&guest, &created);
+    same(&guest, &created);
     guest.name = improv.name()?;
     guest.hash = improv.pass()?;
     let updated = host.recall.update(&guest).await?;
-    assert_eq!(&guest, &updated);
+    same(&guest, &updated);
     host.recall.delete(&guest).await?;
     Ok(())
 }
 
 pub async fn guest_check(host: &mut Host) -> Polite<()> {
-    let mut improv = Improv::default();
+    let mut improv = Improv::seeded(22);
     let guest = improv.guest()?;
     trace!("Guest: {:#?}", &guest);
     let created = host.recall.create(&guest).await?;
-    assert_eq!(&guest, &created);
+    same(&guest, &created);
     let app = host.bearing();
     // tokio::spawn(async move {
     //         axum::serve(listener, app);
     //     });
-    let uri = format!("/guests");
+    let uri = "/guests".to_string();
     let response = app
         .oneshot(
             Request::builder()
@@ -44,14 +55,14 @@ pub async fn guest_check(host: &mut Host) -> Polite<()> {
     let body = response.into_body().collect().await?.to_bytes();
     let body: Vec<Guest> = serde_json::from_slice(&body).unwrap();
     trace!("{:#?}", body);
-    assert_eq!(&guest, &body[0]);
+    assert_eq!(&created, &body[0]);
     info!("Lookup all guests successful.");
     Ok(())
 }
 
 pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
     info!("Testing guest lifecycle.");
-    let mut improv = Improv::default();
+    let mut improv = Improv::seeded(23);
     let mut guest = improv.guest()?;
     // let created = host.recall.create(&guest).await?;
     // assert_eq!(&guest, &created);
@@ -59,7 +70,7 @@ pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
     let app = host.bearing();
 
     info!("Creating guest {}.", &guest.name);
-    let uri = format!("/guests");
+    let uri = "/guests".to_string();
     let body = serde_json::json!(&guest);
     let body = serde_json::to_vec(&body)?;
     let response = app
@@ -75,7 +86,9 @@ pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
     assert_eq!(response.status(), 200);
     let body = response.into_body().collect().await?.to_bytes();
     let body: Guest = serde_json::from_slice(&body).unwrap();
-    assert_eq!(&guest, &body);
+    assert_ne!(body.id, guest.id);
+    guest.id = body.id;
+    same(&guest, &body);
     info!("Guest creation successful for {}.", &guest.name);
 
     info!("Looking up guest {}.", &guest.name);
@@ -92,7 +105,7 @@ pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
     assert_eq!(response.status(), 200);
     let body = response.into_body().collect().await?.to_bytes();
     let body: Guest = serde_json::from_slice(&body).unwrap();
-    assert_eq!(&guest, &body);
+    same(&guest, &body);
     info!("Guest lookup successful for {}.", &guest.name);
 
     info!("Updating name and hash for guest id {}.", &guest.id);
@@ -114,7 +127,7 @@ pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
     assert_eq!(response.status(), 200);
     let body = response.into_body().collect().await?.to_bytes();
     let body: Guest = serde_json::from_slice(&body).unwrap();
-    assert_eq!(&guest, &body);
+    same(&guest, &body);
     info!("Guest update successful for {}.", &guest.name);
 
     info!("Checking out {}.", &guest.name);
@@ -138,3 +151,629 @@ pub async fn guest_lifecycle(host: &mut Host) -> Polite<()> {
 
     Ok(())
 }
+
+pub async fn guest_timestamps(host: &Host) -> Polite<()> {
+    info!("Testing guest timestamps.");
+    let mut improv = Improv::seeded(26);
+    let mut guest = improv.guest()?;
+    let created = host.recall.create(&guest).await?;
+    same(&guest, &created);
+    assert_eq!(created.created_at, created.updated_at);
+    assert!(created.last_login_at.is_none());
+
+    guest.name = improv.name()?;
+    let updated = host.recall.update(&guest).await?;
+    same(&guest, &updated);
+    assert_eq!(created.created_at, updated.created_at);
+    assert!(updated.updated_at > created.updated_at);
+
+    let logged = host.recall.record_login(guest.id).await?;
+    assert_eq!(updated.updated_at, logged.updated_at);
+    assert!(logged.last_login_at.is_some());
+
+    let later = host.recall.create(&improv.guest()?).await?;
+    let mut inquiry = Inquiry::new();
+    inquiry.sort = Some(Sort::CreatedAt);
+    inquiry.order = Some(Order::Desc);
+    let guests = host.recall.inquire(&inquiry).await?;
+    assert_eq!(later.id, guests[0].id);
+
+    inquiry.created_after = Some(later.created_at);
+    let guests = host.recall.inquire(&inquiry).await?;
+    assert!(guests.iter().all(|g| g.created_at >= later.created_at));
+    assert!(guests.iter().any(|g| g.id == later.id));
+    assert!(guests.iter().all(|g| g.id != guest.id));
+
+    let app = host.bearing();
+    let uri = format!(
+        "/guests?sort=last_login_at&order=desc&login_after={}",
+        logged
+            .last_login_at
+            .unwrap()
+            .format("%Y-%m-%dT%H:%M:%S%.fZ")
+    );
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let body: Vec<Guest> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body, vec![logged]);
+    info!("Guest timestamps successful.");
+    Ok(())
+}
+
+pub async fn guest_archive(host: &Host) -> Polite<()> {
+    info!("Testing guest archive.");
+    let mut improv = Improv::seeded(27);
+    let guest = host.recall.create(&improv.guest()?).await?;
+    host.recall.delete(&guest).await?;
+    assert!(host.recall.get(guest.id).await.is_err());
+    assert!(host.recall.create(&guest).await.is_err());
+    let app = host.bearing();
+
+    let uri = "/admin/guests/deleted".to_string();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let body: Vec<Guest> = serde_json::from_slice(&body).unwrap();
+    assert!(body
+        .iter()
+        .any(|g| g.id == guest.id && g.deleted_at.is_some()));
+    info!("Deleted guest listing successful.");
+
+    let uri = format!("/admin/guests/{}/restore", &guest.id);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let body: Guest = serde_json::from_slice(&body).unwrap();
+    same(&guest, &body);
+    assert!(body.deleted_at.is_none());
+    same(&guest, &host.recall.get(guest.id).await?);
+    info!("Guest restore successful.");
+
+    let uri = format!("/admin/guests/{}", &guest.id);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::DELETE)
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 400);
+    host.recall.delete(&guest).await?;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::DELETE)
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert!(host.recall.restore(guest.id).await.is_err());
+    info!("Guest purge successful.");
+
+    let mut recall = host.recall.clone();
+    recall.retention = std::time::Duration::ZERO;
+    let guest = recall.create(&improv.guest()?).await?;
+    recall.delete(&guest).await?;
+    let mut namesake = improv.guest()?;
+    namesake.name = guest.name.clone();
+    let created = recall.create(&namesake).await?;
+    same(&namesake, &created);
+    assert!(recall.restore(guest.id).await.is_err());
+    recall.delete(&created).await?;
+    assert!(recall.sweep().await? >= 1);
+    assert!(recall.deleted().await?.iter().all(|g| g.id != created.id));
+    info!("Guest retention successful.");
+    Ok(())
+}
+
+pub async fn guest_batch(host: &Host) -> Polite<()> {
+    info!("Testing guest batches.");
+    let mut improv = Improv::seeded(28);
+    let mut guests = improv.guests(50)?;
+    let created = host.recall.create_many(&guests).await?;
+    assert_eq!(guests.len(), created.len());
+    guests.iter().zip(&created).for_each(|(l, r)| same(l, r));
+
+    let names = improv.names(guests.len())?;
+    guests
+        .iter_mut()
+        .zip(names)
+        .for_each(|(guest, name)| guest.name = name);
+    let updated = host.recall.update_many(&guests).await?;
+    guests.iter().zip(&updated).for_each(|(l, r)| same(l, r));
+
+    let mut stranger = guests.clone();
+    stranger[0].name = improv.name()?;
+    stranger.push(improv.guest()?);
+    assert!(host.recall.update_many(&stranger).await.is_err());
+    same(&guests[0], &host.recall.get(guests[0].id).await?);
+    info!("Atomic batch update successful.");
+
+    host.recall.delete_many(&guests).await?;
+    assert!(host.recall.get(guests[0].id).await.is_err());
+
+    let app = host.bearing();
+    let uri = "/guests/batch".to_string();
+    let guests = improv.guests(10)?;
+    let body = serde_json::to_vec(&guests)?;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(body))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let created: Vec<Guest> = serde_json::from_slice(&body).unwrap();
+    guests.iter().zip(&created).for_each(|(l, r)| {
+        assert_ne!(l.id, r.id);
+        assert_eq!(l.name, r.name);
+    });
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guests)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 400);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::DELETE)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&created)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert!(host.recall.get(created[0].id).await.is_err());
+    info!("Guest batch endpoints successful.");
+    Ok(())
+}
+
+pub async fn guest_ledger(host: &Host) -> Polite<()> {
+    info!("Testing guest ledger.");
+    let mut improv = Improv::seeded(29);
+    let ledger = host.recall.ledger().await?;
+    let first = ledger.create(&improv.guest()?).await?;
+    assert!(host.recall.get(first.id).await.is_err());
+    same(&first, &ledger.get(first.id).await?);
+
+    let savepoint = ledger.savepoint().await?;
+    let dropped = savepoint.create(&improv.guest()?).await?;
+    savepoint.rollback().await?;
+    assert!(ledger.get(dropped.id).await.is_err());
+
+    let savepoint = ledger.savepoint().await?;
+    let nested = savepoint.savepoint().await?;
+    let kept = nested.create_many(&improv.guests(3)?).await?;
+    nested.commit().await?;
+    savepoint.commit().await?;
+    ledger.commit().await?;
+    same(&first, &host.recall.get(first.id).await?);
+    same(&kept[2], &host.recall.get(kept[2].id).await?);
+    assert!(host.recall.get(dropped.id).await.is_err());
+    info!("Ledger savepoints successful.");
+
+    let ledger = host.recall.ledger().await?;
+    let abandoned = ledger.create(&improv.guest()?).await?;
+    let savepoint = ledger.savepoint().await?;
+    ledger.rollback().await?;
+    assert!(savepoint.get(abandoned.id).await.is_err());
+    assert!(host.recall.get(abandoned.id).await.is_err());
+    info!("Ledger rollback successful.");
+
+    async fn enroll(
+        State(recall): State<Recall>,
+        Json(guests): Json<Vec<Guest>>,
+    ) -> Result<Json<Vec<Guest>>, String> {
+        let ledger = recall.ledger().await.map_err(|e| e.to_string())?;
+        let mut created = Vec::new();
+        for guest in &guests {
+            created.push(ledger.create(guest).await.map_err(|e| e.to_string())?);
+        }
+        ledger.commit().await.map_err(|e| e.to_string())?;
+        Ok(Json(created))
+    }
+    let app = Router::new()
+        .route("/enroll", post(enroll))
+        .with_state(host.recall.clone());
+    let guests = improv.guests(2)?;
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/enroll")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guests)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    same(&guests[1], &host.recall.get(guests[1].id).await?);
+    info!("Ledger in handler successful.");
+    Ok(())
+}
+
+pub async fn guest_names(host: &Host) -> Polite<()> {
+    info!("Testing guest names.");
+    assert_eq!(Guest::fold("ALICE"), Guest::fold("alice"));
+    assert_eq!(Guest::fold("Straße"), Guest::fold("STRASSE"));
+    assert_eq!(Guest::fold("ａｌｉｃｅ"), Guest::fold("Alice"));
+
+    let mut improv = Improv::seeded(130);
+    let mut guest = improv.guest()?;
+    guest.name = format!("Straße-{}", &guest.name);
+    let created = host.recall.create(&guest).await?;
+    let shouted = created.name.to_uppercase();
+    same(&created, &host.recall.get_by_name(&shouted).await?);
+    assert!(host.recall.exists_by_name(&shouted).await?);
+    assert!(!host.recall.exists_by_name(&improv.name()?).await?);
+
+    let mut namesake = improv.guest()?;
+    namesake.name = shouted.clone();
+    assert!(host.recall.create(&namesake).await.is_err());
+    info!("Case-insensitive uniqueness successful.");
+
+    let app = host.bearing();
+    let plain = host.recall.create(&improv.guest()?).await?;
+    let uri = format!("/guests/by-name/{}", plain.name.to_uppercase());
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let body: Guest = serde_json::from_slice(&body).unwrap();
+    assert_eq!(&plain, &body);
+
+    host.recall.delete(&plain).await?;
+    let response = app
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 400);
+    assert!(host.recall.exists_by_name(&plain.name).await?);
+    info!("Lookup by name successful.");
+    Ok(())
+}
+
+/// Checks that guests entered before the name keys were added get the keys of [`Guest::fold`],
+/// and that names sharing a key stop the migration until one is renamed.
+pub async fn guest_folding(host: &Host) -> Polite<()> {
+    info!("Testing name key backfill.");
+    let posture = Posture {
+        database: format!("{}_folding", host.posture.database),
+        ..host.posture.clone()
+    };
+    posture.try_delete().await?;
+    posture.create().await?;
+    posture.migrate_to(Posture::NAME_KEY).await?;
+    let book = posture.book();
+    let mut improv = Improv::seeded(30);
+    let legacy = ["Straße", "STRASSE", "ａｌｉｃｅ"];
+    let mut ids = Vec::new();
+    for name in legacy {
+        let guest = improv.guest()?;
+        sqlx::query("INSERT INTO guests (id, name, hash) VALUES ($1, $2, $3)")
+            .bind(guest.id)
+            .bind(name)
+            .bind(&guest.hash)
+            .execute(&book)
+            .await?;
+        ids.push(guest.id);
+    }
+    match posture.migrate().await {
+        Err(FauxPas::Invalid(flaws)) => {
+            trace!("{:#?}", flaws);
+            assert_eq!(flaws.len(), 1);
+            assert_eq!(flaws[0].rule, "unique");
+            assert!(flaws[0].message.contains(&ids[0].to_string()));
+            assert!(flaws[0].message.contains(&ids[1].to_string()));
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    let unkeyed =
+        sqlx::query_scalar::<_, i64>("SELECT count(*) FROM guests WHERE name_key IS NULL")
+            .fetch_one(&book)
+            .await?;
+    assert_eq!(unkeyed, 3);
+    info!("Shared name keys refused.");
+
+    sqlx::query("UPDATE guests SET name = 'Strasse 2' WHERE id = $1")
+        .bind(ids[1])
+        .execute(&book)
+        .await?;
+    posture.migrate().await?;
+    let recall = Recall::new(book.clone());
+    assert_eq!(recall.get_by_name("STRASSE").await?.id, ids[0]);
+    assert_eq!(recall.get_by_name("alice").await?.id, ids[2]);
+    assert!(recall.exists_by_name("strasse 2").await?);
+    book.close().await;
+    posture.delete().await?;
+    info!("Legacy names folded.");
+    Ok(())
+}
+
+pub async fn guest_policy(host: &Host) -> Polite<()> {
+    info!("Testing password policy.");
+    let mut improv = Improv::seeded(33);
+    let mut guest = improv.guest()?;
+    guest.hash = format!("{}1!", &guest.name);
+    let app = host.bearing();
+    let uri = "/guests".to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guest)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    trace!("{:#?}", &flaws);
+    assert!(flaws.iter().any(|f| f.field == "hash" && f.rule == "name"));
+    assert!(host.recall.get(guest.id).await.is_err());
+    info!("Pass containing name rejected.");
+
+    let mut guests = improv.guests(2)?;
+    guests[1].hash = "password".to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/guests/batch")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guests)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    assert!(flaws.iter().all(|f| f.field == "[1].hash"));
+    assert!(flaws.iter().any(|f| f.rule == "common"));
+    assert!(host.recall.get(guests[0].id).await.is_err());
+    info!("Common pass in batch rejected.");
+
+    let mut guest = host.recall.create(&guests[0]).await?;
+    guest.name = improv.name()?;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/guests/{}", &guest.id))
+                .method(http::Method::PUT)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guest)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    guest.hash = "short".to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/guests/{}", &guest.id))
+                .method(http::Method::PUT)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&guest)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    assert!(flaws.iter().any(|f| f.rule == "min_length"));
+    info!("Weak pass on update rejected.");
+
+    let bystander = host.recall.create(&guests[1]).await?;
+    let mut stray = guest.clone();
+    stray.id = bystander.id;
+    stray.name = improv.name()?;
+    stray.hash = improv.pass()?;
+    let (status, flaws) = vet(
+        app.clone(),
+        http::Method::PUT,
+        &format!("/guests/{}", &guest.id),
+        serde_json::to_value(&stray)?,
+    )
+    .await?;
+    assert_eq!(status, 422);
+    assert_eq!(flaws[0].field, "id");
+    assert_eq!(host.recall.get(bystander.id).await?.name, bystander.name);
+    let mut body = serde_json::to_value(&stray)?;
+    body.as_object_mut().ok_or(FauxPas::BadTest)?.remove("id");
+    let (status, _) = vet(
+        app,
+        http::Method::PUT,
+        &format!("/guests/{}", &guest.id),
+        body,
+    )
+    .await?;
+    assert_eq!(status, 200);
+    assert_eq!(host.recall.get(guest.id).await?.name, stray.name);
+    host.recall.delete(&guest).await?;
+    host.recall.delete(&bystander).await?;
+    info!("Update follows the id in the path.");
+    Ok(())
+}
+
+/// Sends `body` to `uri` with `method`, returning the status and the flaws listed, if any.
+async fn vet(
+    app: Router,
+    method: http::Method,
+    uri: &str,
+    body: serde_json::Value,
+) -> Polite<(http::StatusCode, Vec<Flaw>)> {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&body)?))?,
+        )
+        .await?;
+    let status = response.status();
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body)
+        .map(|problem| problem.flaws)
+        .unwrap_or_default();
+    Ok((status, flaws))
+}
+
+pub async fn guest_vetting(host: &Host) -> Polite<()> {
+    info!("Testing guest validation.");
+    let mut improv = Improv::seeded(50);
+    let app = host.bearing();
+    let pass = improv.pass()?;
+    let names = [
+        ("", "length"),
+        ("  ", "charset"),
+        (" padded", "charset"),
+        ("bell\u{7}", "charset"),
+        ("line\nbreak", "charset"),
+    ];
+    for (name, rule) in names {
+        let body = serde_json::json!({ "name": name, "hash": pass });
+        let (status, flaws) = vet(app.clone(), http::Method::POST, "/guests", body).await?;
+        trace!("{:?}: {:?}", name, flaws);
+        assert_eq!(status, 422);
+        assert!(flaws.iter().any(|f| f.field == "name" && f.rule == rule));
+        assert!(!host.recall.exists_by_name(name).await?);
+    }
+    let long = "a".repeat(Guest::NAME_MAX as usize + 1);
+    let body = serde_json::json!({ "name": long, "hash": "b".repeat(2000) });
+    let (status, flaws) = vet(app.clone(), http::Method::POST, "/guests", body).await?;
+    assert_eq!(status, 422);
+    let fields = flaws.iter().map(|f| f.field.as_str()).collect::<Vec<_>>();
+    assert_eq!(fields, ["hash", "name"]);
+    let messages = flaws.iter().map(|f| f.message.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            format!("Must be at most {} characters.", Guest::PASS_MAX),
+            format!("Must be from 1 to {} characters.", Guest::NAME_MAX),
+        ]
+    );
+    info!("Invalid names refused.");
+
+    let name = format!("{} Ümlaut", improv.name()?);
+    let body = serde_json::json!({ "id": uuid::Uuid::nil(), "name": name, "hash": pass });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/guests")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&body)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let mut guest: Guest = serde_json::from_slice(&body)?;
+    assert!(!guest.id.is_nil());
+    assert_eq!(guest.name, name);
+    info!("Id assigned by the server.");
+
+    guest.name = "tab\tname".to_string();
+    let body = serde_json::to_value(&guest)?;
+    let uri = format!("/guests/{}", guest.id);
+    let (status, flaws) = vet(app.clone(), http::Method::PUT, &uri, body).await?;
+    assert_eq!(status, 422);
+    assert_eq!(flaws[0].field, "name");
+    host.recall.delete(&guest).await?;
+
+    let mut batch = improv.guests(3)?;
+    batch[1].name = String::new();
+    batch[2].name = "bell\u{7}".to_string();
+    let body = serde_json::to_value(&batch)?;
+    let (status, flaws) = vet(app, http::Method::POST, "/guests/batch", body).await?;
+    assert_eq!(status, 422);
+    let fields = flaws.iter().map(|f| f.field.as_str()).collect::<Vec<_>>();
+    assert_eq!(fields, ["[1].name", "[2].name"]);
+    assert!(!host.recall.exists_by_name(&batch[0].name).await?);
+    info!("Invalid batch refused.");
+    Ok(())
+}
+
+pub async fn guest_seeding(host: &Host) -> Polite<()> {
+    info!("Testing seeding.");
+    let mut rehearsal = Rehearsal::new(42, 30);
+    rehearsal.batch = 7;
+    let fixtures = rehearsal.fixtures()?;
+    assert_eq!(fixtures, rehearsal.fixtures()?);
+
+    let seeding = rehearsal.seed(&host.recall).await?;
+    assert_eq!((seeding.seed, seeding.count, seeding.planted), (42, 30, 30));
+    for fixture in &fixtures {
+        same(fixture, &host.recall.get(fixture.id).await?);
+    }
+    let again = rehearsal.seed(&host.recall).await?;
+    assert_eq!(again.planted, 0);
+    assert_eq!(again.planted_at, seeding.planted_at);
+    info!("Seeding is idempotent.");
+
+    let mut changed = Rehearsal::new(42, 31);
+    changed.batch = 7;
+    match changed.seed(&host.recall).await {
+        Err(FauxPas::Invalid(flaws)) => assert_eq!(flaws[0].rule, "conflict"),
+        other => panic!("Expected a conflict, found {:?}", other),
+    }
+
+    let mut renamed = fixtures[0].clone();
+    renamed.name = Improv::default().name()?;
+    host.recall.update(&renamed).await?;
+    host.recall.delete(&fixtures[1]).await?;
+    let reset = rehearsal.reset(&host.recall).await?;
+    assert_eq!(reset.planted, 30);
+    same(&fixtures[0], &host.recall.get(fixtures[0].id).await?);
+    same(&fixtures[1], &host.recall.get(fixtures[1].id).await?);
+    info!("Reset successful.");
+
+    let reset = changed.reset(&host.recall).await?;
+    assert_eq!((reset.count, reset.planted), (31, 31));
+    let ledger = host.recall.ledger().await?;
+    let staged = Rehearsal::new(43, 5).stage(&ledger).await?;
+    assert_eq!(staged.len(), 5);
+    ledger.rollback().await?;
+    assert!(host.recall.get(staged[0].id).await.is_err());
+    host.recall.delete_many(&changed.fixtures()?).await?;
+    Ok(())
+}
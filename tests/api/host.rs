@@ -1,5 +1,10 @@
+use axum::body::Body;
+use axum::http::{self, header, Method, Request};
+use axum::Router;
 use cordial::prelude::*;
+use http_body_util::BodyExt;
 use secrecy::ExposeSecret;
+use tower::ServiceExt;
 use tracing::{info, trace};
 
 pub async fn local_posture(host: &Host) -> Polite<()> {
@@ -25,3 +30,592 @@ pub async fn local_posture(host: &Host) -> Polite<()> {
     info!("Local posture test successful.");
     Ok(())
 }
+
+pub async fn serving(host: &Host) -> Polite<()> {
+    let served = Host::new(host.posture.clone());
+    let book = served.recall.book.clone();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let mut venue = Venue::new();
+    venue.grace = std::time::Duration::from_secs(5);
+    let server = tokio::spawn(async move {
+        served
+            .serve_until(listener, &venue, async {
+                stopped.await.ok();
+            })
+            .await
+    });
+    let response = reqwest::get(format!("http://{}/health/live", addr))
+        .await
+        .map_err(std::io::Error::other)?;
+    assert_eq!(response.status(), 200);
+    trace!("Served health check at {}.", addr);
+    stop.send(()).ok();
+    server.await.map_err(std::io::Error::other)??;
+    assert!(book.is_closed());
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    info!("Graceful shutdown successful.");
+    Ok(())
+}
+
+/// Sends a CORS preflight for a `POST` to `/guests` from `origin`.
+async fn preflight(router: Router, origin: &str) -> Polite<http::Response<Body>> {
+    let response = router
+        .oneshot(
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/guests")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+                .body(Body::empty())?,
+        )
+        .await?;
+    Ok(response)
+}
+
+pub async fn welcoming(host: &Host) -> Polite<()> {
+    let mut welcome = Welcome::new();
+    welcome.origins = vec![
+        "https://app.example.com".to_string(),
+        "https://*.example.org".to_string(),
+    ];
+    welcome.credentials = true;
+    let router = host.welcoming(&welcome)?;
+
+    for origin in ["https://app.example.com", "https://a.b.example.org"] {
+        let response = preflight(router.clone(), origin).await?;
+        assert_eq!(response.status(), 200);
+        let headers = response.headers();
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "3600");
+        let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS].as_bytes();
+        assert!(methods.windows(4).any(|method| method == b"POST"));
+        trace!("Preflight from {} allowed.", origin);
+    }
+    for origin in [
+        "https://example.org",
+        "http://a.example.org",
+        "https://evil.com",
+        "http://127.0.0.1:8080",
+    ] {
+        let response = preflight(router.clone(), origin).await?;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        trace!("Preflight from {} refused.", origin);
+    }
+
+    let response = router
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(header::ORIGIN, "https://app.example.com")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .headers()
+            .get_all(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .iter()
+            .count(),
+        1
+    );
+
+    let response = preflight(host.welcoming(&Welcome::new())?, "http://127.0.0.1:8000").await?;
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://127.0.0.1:8000"
+    );
+
+    welcome.origins = vec![Welcome::ANY.to_string(), "example.com".to_string()];
+    welcome.methods = vec!["GET".to_string(), "BAD METHOD".to_string()];
+    match host.welcoming(&welcome) {
+        Err(FauxPas::Invalid(flaws)) => {
+            let rules = flaws
+                .iter()
+                .map(|flaw| (flaw.field.as_str(), flaw.rule.as_str()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                rules,
+                [
+                    ("origins", "format"),
+                    ("methods", "format"),
+                    ("origins", "credentials")
+                ]
+            );
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    info!("Welcoming test successful.");
+    Ok(())
+}
+
+/// Sends a `GET` to `uri` on `router`, returning the status and body.
+async fn probe(router: Router, uri: &str) -> Polite<(http::StatusCode, Vec<u8>)> {
+    let response = router
+        .oneshot(Request::builder().uri(uri).body(Body::empty())?)
+        .await?;
+    let status = response.status();
+    let body = response.into_body().collect().await?.to_bytes();
+    Ok((status, body.to_vec()))
+}
+
+pub async fn vitals(host: &Host) -> Polite<()> {
+    let (status, _) = probe(host.bearing(), "/health/live").await?;
+    assert_eq!(status, 200);
+    let (status, body) = probe(host.bearing(), "/health/ready").await?;
+    assert_eq!(status, 200);
+    let vitals: Vitals = serde_json::from_slice(&body)?;
+    trace!("{:#?}", vitals);
+    assert!(vitals.ready);
+    assert!(vitals.database.reachable);
+    assert!(vitals.database.version.is_some());
+    assert!(vitals.migrations.current);
+    assert!(vitals.migrations.pending.is_empty());
+    assert_eq!(
+        vitals.migrations.applied,
+        host.posture.status().await?.len()
+    );
+    assert_eq!(vitals.pool.max, 5);
+    assert!((0.0..=1.0).contains(&vitals.pool.saturation));
+    let (status, _) = probe(host.bearing(), "/book").await?;
+    assert_eq!(status, 200);
+
+    let mut posture = host.posture.clone();
+    posture.port = 1;
+    let unreachable = Host::new(posture);
+    let (status, _) = probe(unreachable.bearing(), "/health/live").await?;
+    assert_eq!(status, 200);
+    let (status, body) = probe(unreachable.bearing(), "/health/ready").await?;
+    assert_eq!(status, 503);
+    let vitals: Vitals = serde_json::from_slice(&body)?;
+    assert!(!vitals.ready);
+    assert!(!vitals.database.reachable);
+    assert!(vitals.database.error.is_some());
+    assert!(!vitals.migrations.current);
+    let (status, body) = probe(unreachable.bearing(), "/book").await?;
+    assert_eq!(status, 503);
+    trace!("Book unavailable: {}", String::from_utf8_lossy(&body));
+
+    let mut posture = host.posture.clone();
+    posture.database = format!("{}_pending", posture.database);
+    posture.try_delete().await?;
+    posture.create().await?;
+    let pending = Host::new(posture.clone());
+    let (status, body) = probe(pending.bearing(), "/health/ready").await?;
+    pending.recall.book.close().await;
+    posture.delete().await?;
+    assert_eq!(status, 503);
+    let vitals: Vitals = serde_json::from_slice(&body)?;
+    assert!(!vitals.ready);
+    assert!(vitals.database.reachable);
+    assert!(!vitals.migrations.current);
+    assert_eq!(vitals.migrations.applied, 0);
+    assert!(!vitals.migrations.pending.is_empty());
+    info!("Vitals test successful.");
+    Ok(())
+}
+
+/// The methods probed on every documented path.
+const METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::DELETE,
+    Method::PATCH,
+];
+
+/// Fails if the OpenAPI document served at `/openapi.json` drifts from the router: every
+/// documented operation must be routed, and every other method on a documented path must be
+/// refused.  Requests carry no body, so handlers expecting one reject them before acting.
+pub async fn charter(host: &Host) -> Polite<()> {
+    let (status, body) = probe(host.bearing(), "/openapi.json").await?;
+    assert_eq!(status, 200);
+    let charter: serde_json::Value = serde_json::from_slice(&body)?;
+    assert!(charter["openapi"]
+        .as_str()
+        .is_some_and(|v| v.starts_with("3.1")));
+    let paths = charter["paths"].as_object().ok_or(FauxPas::BadTest)?;
+    assert!(!paths.is_empty());
+    let mut operations = 0;
+    for (path, item) in paths {
+        let uri = path
+            .replace("{id}", &uuid::Uuid::new_v4().to_string())
+            .replace("{name}", "nobody-at-all");
+        assert!(!uri.contains('{'), "Unknown placeholder in {}.", path);
+        for method in METHODS {
+            let documented = item.get(method.as_str().to_lowercase()).is_some();
+            let response = host
+                .bearing()
+                .oneshot(
+                    Request::builder()
+                        .method(method.clone())
+                        .uri(&uri)
+                        .body(Body::empty())?,
+                )
+                .await?;
+            let routed = !matches!(response.status().as_u16(), 404 | 405);
+            assert_eq!(
+                documented,
+                routed,
+                "{} {} is {} but answered {}.",
+                method,
+                path,
+                if documented {
+                    "documented"
+                } else {
+                    "undocumented"
+                },
+                response.status()
+            );
+            if documented {
+                operations += 1;
+                let operation = &item[method.as_str().to_lowercase()];
+                assert!(
+                    operation["responses"].get("200").is_some(),
+                    "{} {}",
+                    method,
+                    path
+                );
+                let summary = operation["summary"].as_str().unwrap_or_default();
+                assert!(
+                    !summary.is_empty() && !summary.contains('\n') && !summary.contains("///"),
+                    "{} {} has a malformed summary.",
+                    method,
+                    path
+                );
+            }
+        }
+    }
+    trace!(
+        "Checked {} operations on {} paths.",
+        operations,
+        paths.len()
+    );
+
+    let routes = routes(&host.bearing());
+    assert!(!routes.is_empty());
+    for path in &routes {
+        let item = paths
+            .get(path)
+            .unwrap_or_else(|| panic!("{} is routed but undocumented.", path));
+        let uri = path
+            .replace("{id}", &uuid::Uuid::new_v4().to_string())
+            .replace("{name}", "nobody-at-all");
+        let response = host
+            .bearing()
+            .oneshot(
+                Request::builder()
+                    .method(Method::from_bytes(b"PROBE").map_err(|_| FauxPas::BadTest)?)
+                    .uri(&uri)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), 405, "{} answered PROBE.", path);
+        let allow = response
+            .headers()
+            .get(header::ALLOW)
+            .ok_or(FauxPas::BadTest)?
+            .to_str()
+            .map_err(|_| FauxPas::BadTest)?
+            .to_lowercase();
+        for method in allow.split(',').map(str::trim).filter(|m| *m != "head") {
+            assert!(
+                item.get(method).is_some(),
+                "{} {} is routed but undocumented.",
+                method,
+                path
+            );
+        }
+    }
+    trace!("Checked {} routed paths.", routes.len());
+
+    let schemas = charter["components"]["schemas"]
+        .as_object()
+        .ok_or(FauxPas::BadTest)?;
+    for schema in ["Guest", "Pass", "Flaw", "Vitals"] {
+        assert!(schemas.contains_key(schema), "Missing schema {}.", schema);
+    }
+    let fields = &schemas["Guest"]["properties"];
+    assert_eq!(fields["name"]["minLength"], 1);
+    assert_eq!(fields["name"]["maxLength"], Guest::NAME_MAX);
+    assert_eq!(fields["hash"]["maxLength"], Guest::PASS_MAX);
+    assert!(fields["name"]["description"].is_string());
+    let refs = body
+        .windows(b"#/components/schemas/".len())
+        .enumerate()
+        .filter(|(_, w)| *w == b"#/components/schemas/")
+        .map(|(i, w)| {
+            let rest = &body[i + w.len()..];
+            let end = rest.iter().position(|b| *b == b'"').unwrap_or(rest.len());
+            String::from_utf8_lossy(&rest[..end]).to_string()
+        })
+        .collect::<Vec<_>>();
+    for reference in refs {
+        assert!(
+            schemas.contains_key(&reference),
+            "Dangling reference to {}.",
+            reference
+        );
+    }
+    info!("Charter test successful.");
+    Ok(())
+}
+
+/// Lists the paths routed by `router`, written as in the charter.  The router does not expose its
+/// routes, so they are read from its debug output, less the `/` and catch-all paths axum adds for
+/// its fallback.
+fn routes(router: &Router) -> Vec<String> {
+    let debug = format!("{:?}", router);
+    let mut routes = debug
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|s| s.starts_with('/') && *s != "/" && !s.contains("__private__axum"))
+        .map(|s| {
+            s.split('/')
+                .map(|p| match p.strip_prefix(':') {
+                    Some(name) => format!("{{{}}}", name),
+                    None => p.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<Vec<_>>();
+    routes.sort();
+    routes.dedup();
+    routes
+}
+
+/// Reads the [`Problem`] answered by `response`, checking its media type.
+async fn problem(response: http::Response<Body>) -> Polite<Problem> {
+    let media = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .ok_or(FauxPas::BadTest)?;
+    assert_eq!(media.as_bytes(), Problem::CONTENT_TYPE.as_bytes());
+    let body = response.into_body().collect().await?.to_bytes();
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Sends a request with a JSON `body` to `router`, carrying `id` as its request id if set.
+async fn ushered(
+    router: Router,
+    method: Method,
+    uri: &str,
+    body: &'static str,
+    id: Option<&str>,
+) -> Polite<http::Response<Body>> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(id) = id {
+        request = request.header(Usher::REQUEST_ID, id);
+    }
+    Ok(router.oneshot(request.body(Body::from(body))?).await?)
+}
+
+/// Reads the request id echoed in `response`.
+fn echoed(response: &http::Response<Body>) -> Polite<String> {
+    let id = response
+        .headers()
+        .get(Usher::REQUEST_ID)
+        .ok_or(FauxPas::BadTest)?;
+    Ok(String::from_utf8_lossy(id.as_bytes()).to_string())
+}
+
+pub async fn escorting(host: &Host) -> Polite<()> {
+    let first = ushered(host.bearing(), Method::GET, "/health/live", "", None).await?;
+    assert_eq!(first.status(), 200);
+    let first = echoed(&first)?;
+    assert!(uuid::Uuid::parse_str(&first).is_ok());
+    let second = ushered(host.bearing(), Method::GET, "/health/live", "", None).await?;
+    assert_ne!(echoed(&second)?, first);
+
+    let refused = ushered(
+        host.bearing(),
+        Method::POST,
+        "/improv/pass",
+        r#"{"length":0}"#,
+        Some("errand-45"),
+    )
+    .await?;
+    assert_eq!(refused.status(), 422);
+    assert_eq!(echoed(&refused)?, "errand-45");
+    let refusal = problem(refused).await?;
+    assert_eq!(refusal.status, 422);
+    assert!(!refusal.detail.is_empty());
+    assert_eq!(refusal.request_id.as_deref(), Some("errand-45"));
+    let flawed = ushered(
+        host.bearing(),
+        Method::POST,
+        "/guests",
+        r#"{"name":"","hash":""}"#,
+        Some("flawed-45"),
+    )
+    .await?;
+    assert_eq!(flawed.status(), 422);
+    let refusal = problem(flawed).await?;
+    assert!(refusal.flaws.iter().any(|flaw| flaw.field == "name"));
+    assert_eq!(refusal.request_id.as_deref(), Some("flawed-45"));
+    let uri = format!("/guests/{}", uuid::Uuid::new_v4());
+    let unknown = ushered(host.bearing(), Method::GET, &uri, "", Some("stray-45")).await?;
+    assert_eq!(unknown.status(), 400);
+    let refusal = problem(unknown).await?;
+    assert_eq!(refusal.status, 400);
+    assert!(refusal.flaws.is_empty());
+    assert_eq!(refusal.request_id.as_deref(), Some("stray-45"));
+    let missing = ushered(host.bearing(), Method::GET, "/nowhere", "", Some("lost-45")).await?;
+    assert_eq!(missing.status(), 404);
+    assert_eq!(echoed(&missing)?, "lost-45");
+
+    let router = host.welcoming(&Welcome::new())?;
+    let response = router
+        .oneshot(
+            Request::builder()
+                .uri("/health/live")
+                .header(header::ORIGIN, "http://localhost:8080")
+                .body(Body::empty())?,
+        )
+        .await?;
+    let exposed = response
+        .headers()
+        .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+        .ok_or(FauxPas::BadTest)?;
+    assert_eq!(exposed.as_bytes(), b"x-request-id");
+    trace!("Request id {} exposed.", echoed(&response)?);
+    info!("Escorting test successful.");
+    Ok(())
+}
+
+pub async fn tally(host: &Host) -> Polite<()> {
+    let router = host.bearing();
+    let (status, _) = probe(router.clone(), "/health/live").await?;
+    assert_eq!(status, 200);
+    let (status, _) = probe(router.clone(), "/improv/names?count=3").await?;
+    assert_eq!(status, 200);
+    let guest = Improv::seeded(46).guest()?;
+    host.recall.create(&guest).await?;
+    host.recall.get(guest.id).await?;
+    assert!(host.recall.get(uuid::Uuid::new_v4()).await.is_err());
+
+    let response = router
+        .oneshot(Request::builder().uri("/metrics").body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .ok_or(FauxPas::BadTest)?;
+    assert_eq!(content_type.as_bytes(), Tally::CONTENT_TYPE.as_bytes());
+    let body = response.into_body().collect().await?.to_bytes();
+    let metrics = String::from_utf8_lossy(&body);
+    trace!("{}", metrics);
+    let sample = |prefix: &str| {
+        metrics
+            .lines()
+            .find(|line| line.starts_with(prefix))
+            .and_then(|line| line.rsplit(' ').next())
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+    for expected in [
+        format!("# TYPE {} counter", Tally::REQUESTS),
+        format!("# TYPE {} histogram", Tally::REQUEST_SECONDS),
+        format!("# TYPE {} histogram", Recall::QUERY_SECONDS),
+        format!("# TYPE {} gauge", Tally::POOL_CONNECTIONS),
+        format!("# TYPE {} counter", Improv::GENERATED),
+        format!("# HELP {} Requests answered by the host.", Tally::REQUESTS),
+    ] {
+        assert!(metrics.contains(&expected), "Missing {}.", expected);
+    }
+    let live = format!(
+        r#"{}{{method="GET",route="/health/live",status="200"}}"#,
+        Tally::REQUESTS
+    );
+    assert!(sample(&live).is_some_and(|n| n >= 1.0));
+    let names = format!(
+        r#"{}_count{{method="GET",route="/improv/names",status="200"}}"#,
+        Tally::REQUEST_SECONDS
+    );
+    assert!(sample(&names).is_some_and(|n| n >= 1.0));
+    for (operation, outcome) in [("create", "ok"), ("get", "ok"), ("get", "error")] {
+        let query = format!(
+            r#"{}_count{{operation="{}",outcome="{}"}}"#,
+            Recall::QUERY_SECONDS,
+            operation,
+            outcome
+        );
+        assert!(
+            sample(&query).is_some_and(|n| n >= 1.0),
+            "Missing {}.",
+            query
+        );
+    }
+    let generated = format!(r#"{}{{kind="name"}}"#, Improv::GENERATED);
+    assert!(sample(&generated).is_some_and(|n| n >= 3.0));
+    assert_eq!(sample(Tally::POOL_MAX), Some(5.0));
+    assert!(sample(&format!(r#"{}{{state="idle"}}"#, Tally::POOL_CONNECTIONS)).is_some());
+    host.recall.delete(&guest).await?;
+    info!("Tally test successful.");
+    Ok(())
+}
+
+pub async fn bounding(host: &Host) -> Polite<()> {
+    info!("Testing body and time limits.");
+    assert_eq!(host.bounds, Bounds::new());
+    let tight = Bounds {
+        body: 64,
+        ..Bounds::new()
+    };
+    let guest = Improv::seeded(50).guest()?;
+    let response = host
+        .bounded(&tight)?
+        .bearing()
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/guests")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&guest)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 413);
+    assert!(!host.recall.exists_by_name(&guest.name).await?);
+    info!("Large body refused.");
+
+    let hasty = Bounds {
+        timeout: std::time::Duration::from_millis(10),
+        ..Bounds::new()
+    };
+    let slow = Router::new().route(
+        "/slow",
+        axum::routing::get(|| async {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        }),
+    );
+    let (status, _) = probe(hasty.bound(slow), "/slow").await?;
+    assert_eq!(status, 503);
+    let (status, _) = probe(host.bounded(&hasty)?.bearing(), "/health/live").await?;
+    assert_eq!(status, 200);
+    info!("Slow request timed out.");
+
+    let empty = Bounds {
+        body: 0,
+        timeout: std::time::Duration::ZERO,
+    };
+    match empty.validate() {
+        Err(FauxPas::Invalid(flaws)) => {
+            let fields = flaws.iter().map(|f| f.field.as_str()).collect::<Vec<_>>();
+            assert_eq!(fields, ["body", "timeout"]);
+        }
+        _ => return Err(FauxPas::BadTest),
+    }
+    assert!(host.bounded(&empty).is_err());
+    Ok(())
+}
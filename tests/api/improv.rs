@@ -13,12 +13,27 @@ pub async fn improvise(host: &Host) -> Polite<()> {
     guest_pass(host).await?;
     pass_adv(host).await?;
     info!("Pass recommendations acceptable.");
+    info!("Checking passphrase recommendations.");
+    passphrase(host).await?;
+    info!("Passphrase recommendations acceptable.");
+    info!("Checking vacant names.");
+    vacancy(host).await?;
+    info!("Vacant names acceptable.");
+    info!("Checking name patterns.");
+    patterns(host).await?;
+    info!("Name patterns acceptable.");
+    info!("Checking bulk recommendations.");
+    bulk(host).await?;
+    info!("Bulk recommendations acceptable.");
+    info!("Checking pass scores.");
+    score(host).await?;
+    info!("Pass scores acceptable.");
     Ok(())
 }
 
 async fn guest_name(host: &Host) -> Polite<()> {
     let app = host.bearing();
-    let uri = format!("/improv/name");
+    let uri = "/improv/name".to_string();
     let response = app
         .clone()
         .oneshot(
@@ -32,7 +47,7 @@ async fn guest_name(host: &Host) -> Polite<()> {
     let body = response.into_body().collect().await?.to_bytes();
     info!("New name: {:#?}", &body);
 
-    let uri = format!("/improv/name/num");
+    let uri = "/improv/name/num".to_string();
     let response = app
         .oneshot(
             Request::builder()
@@ -49,7 +64,7 @@ async fn guest_name(host: &Host) -> Polite<()> {
 
 async fn guest_pass(host: &Host) -> Polite<()> {
     let app = host.bearing();
-    let uri = format!("/improv/pass");
+    let uri = "/improv/pass".to_string();
     let response = app
         .oneshot(
             Request::builder()
@@ -66,7 +81,7 @@ async fn guest_pass(host: &Host) -> Polite<()> {
 
 async fn pass_adv(host: &Host) -> Polite<()> {
     let app = host.bearing();
-    let uri = format!("/improv/pass");
+    let uri = "/improv/pass".to_string();
     let mut pass = Pass::new();
     pass.length = 20;
     pass.numbers = false;
@@ -80,6 +95,7 @@ async fn pass_adv(host: &Host) -> Polite<()> {
     let body = serde_json::json!(&pass);
     let body = serde_json::to_vec(&body)?;
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
                 .uri(&uri)
@@ -91,5 +107,385 @@ async fn pass_adv(host: &Host) -> Polite<()> {
     assert_eq!(response.status(), 200);
     let body = response.into_body().collect().await?.to_bytes();
     info!("New pass: {:#?}", &body);
+    let mut improv = Improv::from_pass(pass.into(), false);
+    assert_eq!(improv.pass()?.chars().count(), 20);
+
+    pass.uppercase = false;
+    pass.symbols = false;
+    pass.spaces = false;
+    pass.length = 1_000_000;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&pass)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    let rules = flaws
+        .iter()
+        .map(|flaw| (flaw.field.as_str(), flaw.rule.as_str()))
+        .collect::<Vec<(&str, &str)>>();
+    assert_eq!(rules, [("length", "range"), ("classes", "required")]);
+
+    pass.length = 2;
+    pass.numbers = true;
+    pass.lowercase = true;
+    pass.uppercase = true;
+    assert!(pass.validate().is_err());
+    pass.length = 3;
+    pass.validate()?;
+    Ok(())
+}
+
+/// A [`Roster`] where every name is taken unless it ends with "-5".
+struct Crowded;
+
+#[async_trait::async_trait]
+impl Roster for Crowded {
+    async fn taken(&self, name: &str) -> Polite<bool> {
+        Ok(!name.ends_with("-5"))
+    }
+}
+
+async fn vacancy(host: &Host) -> Polite<()> {
+    let mut improv = Improv::seeded(31);
+    let taken = host.recall.create(&improv.guest()?).await?;
+    let fresh = improv.name()?;
+    let names = vec![
+        taken.name.clone(),
+        fresh.clone(),
+        taken.name.to_uppercase(),
+        fresh.to_uppercase(),
+    ];
+    assert_eq!(host.recall.vacant(&names).await?, vec![fresh.clone()]);
+
+    let mut vacancy = Vacancy::new();
+    vacancy.count = Some(4);
+    let suggestions = vacancy.suggest(&host.recall, &taken.name).await?;
+    assert_eq!(suggestions.len(), 4);
+    assert!(suggestions
+        .iter()
+        .all(|name| Guest::fold(name) != taken.key()));
+    assert!(Improv::distance(&taken.name, &suggestions[0]) <= 2);
+    let suggestions = vacancy.suggest(&host.recall, &fresh).await?;
+    assert_eq!(suggestions[0], fresh);
+
+    let name = Vacancy::new().name(&Crowded).await?;
+    assert!(name.ends_with("-5"));
+    let mut narrow = Vacancy::new();
+    narrow.pattern = Some("x".to_string());
+    narrow.count = Some(1);
+    assert_eq!(narrow.offer(&Crowded).await?, ["x-5"]);
+    narrow.count = Some(2);
+    match narrow.offer(&Crowded).await {
+        Err(FauxPas::Invalid(flaws)) => assert_eq!(flaws[0].field, "count"),
+        _ => return Err(FauxPas::BadTest),
+    }
+    info!("Vacancy checks successful.");
+
+    let app = host.bearing();
+    let uri = "/improv/name?available=true".to_string();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let name = String::from_utf8(body.to_vec()).unwrap();
+    assert!(!host.recall.exists_by_name(&name).await?);
+
+    let uri = format!("/improv/name?available=true&near={}&count=3", &taken.name);
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let body: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body.len(), 3);
+    assert!(!body.contains(&taken.name));
+
+    for (pattern, count) in [("x", 2), ("%7Bdigits:1%7D", 11)] {
+        let uri = format!(
+            "/improv/name?available=true&pattern={}&count={}",
+            pattern, count
+        );
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), 200);
+        let body = response.into_body().collect().await?.to_bytes();
+        let mut names: Vec<String> = serde_json::from_slice(&body)?;
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+    info!("Vacant name routes successful.");
+
+    let counts = ["0", "1001", "100000000"];
+    let wanted = [
+        "",
+        "&near=alice",
+        "&available=true",
+        "&available=true&near=alice",
+    ];
+    for uri in counts
+        .iter()
+        .flat_map(|count| wanted.map(|w| format!("/improv/name?count={}{}", count, w)))
+    {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), 422);
+        let body = response.into_body().collect().await?.to_bytes();
+        let flaws = serde_json::from_slice::<Problem>(&body)?.flaws;
+        assert_eq!(flaws[0].field, "count");
+    }
+    vacancy.count = Some(Vacancy::MAX + 1);
+    assert!(vacancy.suggest(&host.recall, &fresh).await.is_err());
+    assert!(vacancy.offer(&host.recall).await.is_err());
+    info!("Oversized counts refused.");
+    Ok(())
+}
+
+async fn passphrase(host: &Host) -> Polite<()> {
+    assert_eq!(Passphrase::wordlist().len(), 7776);
+    let mut improv = Improv::seeded(32);
+    let mut config = Passphrase::new();
+    config.separator = ".".to_string();
+    let phrase = improv.passphrase(&config)?;
+    assert_eq!(phrase.passphrase.split('.').count(), 6);
+    assert!((phrase.entropy - 77.55).abs() < 0.01);
+
+    config.words = 0;
+    assert!(improv.passphrase(&config).is_err());
+
+    config.words = 4;
+    config.separator = " ".to_string();
+    config.capitalize = true;
+    config.digit = true;
+    config.symbol = true;
+    let app = host.bearing();
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/improv/passphrase")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&config)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let phrase: Phrase = serde_json::from_slice(&body).unwrap();
+    info!("New passphrase: {:#?}", &phrase);
+    let words = phrase.passphrase.split(' ').collect::<Vec<&str>>();
+    assert_eq!(words.len(), 4);
+    assert!(words
+        .iter()
+        .all(|word| word.starts_with(char::is_uppercase)));
+    assert!(phrase.passphrase.contains(|c: char| c.is_ascii_digit()));
+    assert!(phrase
+        .passphrase
+        .contains(|c| Passphrase::SYMBOLS.contains(c)));
+    assert_eq!(phrase.entropy, config.entropy());
+    Ok(())
+}
+
+async fn score(host: &Host) -> Polite<()> {
+    let app = host.bearing();
+    let candidate = Candidate {
+        pass: "password".to_string(),
+        name: None,
+    };
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/improv/score")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&candidate)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let appraisal: Appraisal = serde_json::from_slice(&body).unwrap();
+    info!("Appraisal: {:#?}", &appraisal);
+    assert!(appraisal.flaws.iter().any(|f| f.rule == "common"));
+
+    let candidate = Candidate {
+        pass: "Velvet-Harbor-Lantern-42".to_string(),
+        name: Some("guest".to_string()),
+    };
+    let appraisal = Policy::default().appraise("pass", candidate.name.as_deref(), &candidate.pass);
+    assert!(appraisal.flaws.is_empty());
+    assert_eq!(appraisal.rating, Appraisal::rating(appraisal.score));
+    Ok(())
+}
+
+pub fn seeded() -> Polite<()> {
+    let fixtures = serde_json::to_vec(&Improv::seeded(7).guests(20)?)?;
+    let again = serde_json::to_vec(&Improv::seeded(7).guests(20)?)?;
+    assert_eq!(fixtures, again);
+    let other = Improv::seeded(8).guests(20)?;
+    assert_ne!(fixtures, serde_json::to_vec(&other)?);
+    assert!(other.iter().all(|guest| guest.id.get_version_num() == 4));
+    info!("Seeded fixtures reproducible.");
+
+    let mut improv = Improv::seeded(7);
+    improv.pass = improv
+        .pass
+        .clone()
+        .length(12)
+        .uppercase_letters(true)
+        .symbols(true)
+        .exclude_similar_characters(true)
+        .strict(true);
+    let passes = improv.passes(20)?;
+    assert!(passes.iter().all(|pass| pass.chars().count() == 12));
+    assert!(passes.iter().all(|pass| pass.contains(char::is_uppercase)
+        && pass.contains(|c: char| c.is_ascii_punctuation())
+        && !pass.contains(['0', '1', 'l', 'I', 'O'])));
+    improv.pass = improv.pass.clone().length(2);
+    assert!(improv.pass().is_err());
+    Ok(())
+}
+
+async fn patterns(host: &Host) -> Polite<()> {
+    let pattern = "{adjective}_{noun}_{digits:2}".parse::<Pattern>()?;
+    assert_eq!(pattern.pieces().len(), 5);
+    let name = Improv::seeded(3).pattern(&pattern, Lexicon::standard())?;
+    let parts = name.split('_').collect::<Vec<&str>>();
+    assert_eq!(parts.len(), 3);
+    assert!(parts[2].len() == 2 && parts[2].chars().all(|c| c.is_ascii_digit()));
+    for template in ["", "{", "{noun", "noun}", "{}", "{digits:0}", "{noun:2}"] {
+        assert!(template.parse::<Pattern>().is_err(), "{}", template);
+    }
+    let braces = "{{{noun}}}".parse::<Pattern>()?;
+    let name = Improv::default().pattern(&braces, Lexicon::standard())?;
+    assert!(name.starts_with('{') && name.ends_with('}'));
+
+    let mut lexicon = Lexicon::new();
+    lexicon.embed("brand", "# house brands\nacme\n\nShit\nglobex\nacme\n");
+    assert_eq!(
+        lexicon.words("brand"),
+        Some(&["acme".to_string(), "globex".to_string()][..])
+    );
+    assert!(!lexicon.clean("holy-SHIT-7"));
+    assert!(lexicon.clean("shitake-7"));
+    let brand = "{brand}-{adjective}".parse::<Pattern>()?;
+    assert!(Improv::default().pattern(&brand, &lexicon).is_err());
+
+    let dir = std::env::temp_dir().join(format!("cordial-lexicon-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("brand.txt"), "initech\nglobex\n")?;
+    std::fs::write(dir.join("blocklist.txt"), "globex\n")?;
+    let mut lexicon = Lexicon::default();
+    lexicon.load_dir(&dir)?;
+    std::fs::remove_dir_all(&dir)?;
+    let names = Improv::seeded(3).patterns(&brand, &lexicon, 10)?;
+    assert!(names.iter().all(|name| name.starts_with("initech-")));
+    info!("Patterns successful.");
+
+    let app = host.bearing();
+    let uri = "/improv/name?pattern=team-%7Bnoun%7D-%7Bdigits%3A3%7D&count=3".to_string();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let names: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(names.len(), 3);
+    assert!(names.iter().all(|name| name.starts_with("team-")));
+
+    let uri = "/improv/name?pattern=%7Bmissing%7D".to_string();
+    let response = app
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    assert_eq!(flaws[0].rule, "wordlist");
+    Ok(())
+}
+
+async fn bulk(host: &Host) -> Polite<()> {
+    let app = host.bearing();
+    let uri = "/improv/names?count=25&seed=11".to_string();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let names: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(names, Improv::seeded(11).names(25)?);
+
+    let mut pass = Pass::new();
+    pass.length = 16;
+    pass.uppercase = true;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/improv/passes?count=40")
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&pass)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let passes: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(passes.len(), 40);
+    assert!(passes.iter().all(|pass| pass.len() == 16));
+
+    pass.length = 0;
+    let uri = format!("/improv/passes?count={}", Bulk::MAX + 1);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .method(http::Method::POST)
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&pass)?))?,
+        )
+        .await?;
+    assert_eq!(response.status(), 422);
+    let body = response.into_body().collect().await?.to_bytes();
+    let flaws = serde_json::from_slice::<Problem>(&body).unwrap().flaws;
+    let fields = flaws
+        .iter()
+        .map(|flaw| flaw.field.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(fields, ["count", "length"]);
+
+    let uri = "/improv/guests".to_string();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let body = response.into_body().collect().await?.to_bytes();
+    let guests: Vec<Guest> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(guests.len(), Bulk::new().count);
+    assert!(host.recall.get(guests[0].id).await.is_err());
+
+    let uri = "/improv/guests?count=0".to_string();
+    let response = app
+        .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+        .await?;
+    assert_eq!(response.status(), 422);
     Ok(())
 }
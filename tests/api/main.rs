@@ -1,11 +1,21 @@
+mod audit;
+mod cli;
+mod concierge;
 mod conduct;
+mod courier;
+mod door;
 mod guest;
 mod host;
 mod improv;
 mod polite;
 
 pub mod prelude {
+    pub use crate::audit::*;
+    pub use crate::cli::*;
+    pub use crate::concierge::*;
     pub use crate::conduct::*;
+    pub use crate::courier::*;
+    pub use crate::door::*;
     pub use crate::guest::*;
     pub use crate::host::*;
     pub use crate::improv::*;
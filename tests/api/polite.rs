@@ -2,7 +2,7 @@ use cordial::prelude::*;
 use tracing::{info, warn};
 
 pub fn fauxpas() -> Polite<()> {
-    let _ = env()?;
+    env()?;
     info!("Env test successful.");
     Ok(())
 }